@@ -0,0 +1,170 @@
+use crate::Coordinate;
+#[cfg(feature = "cargo")]
+use {crate::Error, serde::Deserialize};
+
+/// The sources used by packages from crates.io, via either the git or sparse
+/// registry protocols
+const CRATES_IO_SOURCES: &[&str] = &[
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+/// The reason a cargo package has no equivalent coordinate
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum UnsupportedSource {
+    /// The package is a path dependency, including workspace members, which
+    /// are not published anywhere clearlydefined.io can harvest them from
+    #[error("path dependencies are not supported")]
+    Path,
+    /// The package comes from a registry other than crates.io
+    #[error("registry '{}' is not supported", _0)]
+    Registry(String),
+    /// The package comes from a git repository that isn't on GitHub
+    #[error("git repository '{}' is not supported", _0)]
+    Git(String),
+}
+
+/// A cargo package that was skipped as it has no equivalent coordinate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedPackage {
+    pub name: String,
+    pub version: semver::Version,
+    /// Why the package was skipped
+    pub reason: UnsupportedSource,
+}
+
+/// Creates the coordinate for a cargo package from its source, which is a
+/// `crate/cratesio/-/{name}/{version}` coordinate for crates.io packages, and a
+/// `git/github/{owner}/{repo}/{rev}` coordinate for the locked commit of
+/// packages from a GitHub repository
+pub(crate) fn package_coordinate(
+    name: &str,
+    version: &semver::Version,
+    source: Option<&str>,
+) -> Result<Coordinate, SkippedPackage> {
+    let unsupported = |reason| SkippedPackage {
+        name: name.to_owned(),
+        version: version.clone(),
+        reason,
+    };
+
+    let Some(source) = source else {
+        return Err(unsupported(UnsupportedSource::Path));
+    };
+
+    if CRATES_IO_SOURCES.contains(&source) {
+        return Ok(Coordinate::crates_io(name, version));
+    }
+
+    // eg. git+https://github.com/EmbarkStudios/krates?branch=main#<rev>
+    let Some(repo) = source.strip_prefix("git+") else {
+        return Err(unsupported(UnsupportedSource::Registry(source.to_owned())));
+    };
+
+    let (url, rev) = repo.split_once('#').unwrap_or((repo, ""));
+    github_coordinate(url, rev).ok_or_else(|| unsupported(UnsupportedSource::Git(url.to_owned())))
+}
+
+/// Creates the coordinate for a commit in a GitHub repository, returning `None`
+/// if the url is not a GitHub repository or the revision isn't a full commit
+/// sha
+fn github_coordinate(url: &str, rev: &str) -> Option<Coordinate> {
+    let url = url::Url::parse(url).ok()?;
+    if !url.host_str()?.eq_ignore_ascii_case("github.com") {
+        return None;
+    }
+
+    let mut segments = url.path_segments()?.filter(|seg| !seg.is_empty());
+    let owner = segments.next()?;
+    let repo = segments.next()?;
+    if segments.next().is_some() {
+        return None;
+    }
+
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    Coordinate::github(owner, repo, rev).ok()
+}
+
+/// Splits the packages into the sorted and deduplicated coordinates, as
+/// multiple packages can be built from the same git repository, and the
+/// packages that were skipped
+pub(crate) fn partition(
+    packages: impl Iterator<Item = Result<Coordinate, SkippedPackage>>,
+) -> (Vec<Coordinate>, Vec<SkippedPackage>) {
+    let mut coordinates = Vec::new();
+    let mut skipped = Vec::new();
+
+    for pkg in packages {
+        match pkg {
+            Ok(coord) => coordinates.push(coord),
+            Err(pkg) => skipped.push(pkg),
+        }
+    }
+
+    coordinates.sort();
+    coordinates.dedup();
+
+    (coordinates, skipped)
+}
+
+#[cfg(feature = "cargo")]
+impl Coordinate {
+    /// Parses the contents of a `Cargo.lock` file, returning the sorted and
+    /// deduplicated coordinates of every package from crates.io, or from a
+    /// GitHub repository, along with every package that was skipped, eg. path
+    /// dependencies, and the reason it was skipped. Both the v3 and v4
+    /// lockfile formats are supported.
+    pub fn from_cargo_lock(toml: &str) -> Result<(Vec<Coordinate>, Vec<SkippedPackage>), Error> {
+        #[derive(Deserialize)]
+        struct Package {
+            name: String,
+            version: String,
+            source: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct Lockfile {
+            #[serde(default)]
+            package: Vec<Package>,
+        }
+
+        use anyhow::Context as _;
+
+        let lockfile: Lockfile = toml::from_str(toml).map_err(|err| Error::Generic(err.into()))?;
+
+        let packages = lockfile
+            .package
+            .into_iter()
+            .map(|pkg| {
+                let version = pkg.version.parse().with_context(|| {
+                    format!("invalid version '{}' for '{}'", pkg.version, pkg.name)
+                })?;
+
+                Ok(package_coordinate(
+                    &pkg.name,
+                    &version,
+                    pkg.source.as_deref(),
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(partition(packages.into_iter()))
+    }
+
+    /// Reads the `Cargo.lock` at the specified path, see
+    /// [`Coordinate::from_cargo_lock`]
+    pub fn from_cargo_lock_path(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(Vec<Coordinate>, Vec<SkippedPackage>), Error> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path).map_err(|err| {
+            Error::Generic(anyhow::anyhow!(
+                "failed to read '{}': {}",
+                path.display(),
+                err
+            ))
+        })?;
+
+        Self::from_cargo_lock(&toml)
+    }
+}
@@ -1,3 +1,6 @@
+mod concurrency;
+pub use concurrency::ConcurrencyController;
+
 #[cfg(not(feature = "blocking"))]
 mod r#async;
 
@@ -9,3 +12,50 @@ mod sync;
 
 #[cfg(feature = "blocking")]
 pub use sync::Client;
+
+/// The HTTP protocol version(s) a [`Client`] uses to communicate with the API
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HttpVersion {
+    /// Negotiates the protocol with the server, preferring HTTP/2 if it is
+    /// supported by the server
+    #[default]
+    Negotiate,
+    /// Only use HTTP/1.1, eg. for compatibility with proxies that don't
+    /// support HTTP/2
+    Http1Only,
+    /// Always use HTTP/2 without negotiating it first, which can help when
+    /// running many concurrent batch requests
+    Http2PriorKnowledge,
+}
+
+/// Generates the unique ids that are sent in the `X-Request-Id` header of each
+/// request so that it can be correlated in logs
+pub type RequestIdGenerator = Box<dyn Fn() -> String + Send + Sync>;
+
+/// The default [`RequestIdGenerator`], which generates v4 UUIDs
+pub fn uuid_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+const REQUEST_ID: http::HeaderName = http::HeaderName::from_static("x-request-id");
+
+/// Adds an `X-Request-Id` header to the request if a generator has been
+/// configured and the request doesn't already have one, returning the id the
+/// request will be sent with, if any
+fn add_request_id(
+    req: &mut http::Request<bytes::Bytes>,
+    generator: Option<&RequestIdGenerator>,
+) -> Option<String> {
+    if let Some(generator) = generator {
+        if !req.headers().contains_key(REQUEST_ID) {
+            if let Ok(id) = http::HeaderValue::from_str(&generator()) {
+                req.headers_mut().insert(REQUEST_ID, id);
+            }
+        }
+    }
+
+    req.headers()
+        .get(REQUEST_ID)
+        .and_then(|id| id.to_str().ok())
+        .map(String::from)
+}
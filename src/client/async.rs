@@ -6,11 +6,15 @@ use reqwest::Client as AClient;
 #[derive(Default)]
 pub struct Client {
     inner: AClient,
+    request_id: Option<super::RequestIdGenerator>,
 }
 
 impl From<AClient> for Client {
     fn from(o: AClient) -> Self {
-        Self { inner: o }
+        Self {
+            inner: o,
+            request_id: None,
+        }
     }
 }
 
@@ -19,7 +23,59 @@ impl Client {
         Self::default()
     }
 
-    pub async fn execute<Res>(&self, req: http::Request<bytes::Bytes>) -> Result<Res, Error>
+    /// Creates a client that uses an externally configured [`reqwest::Client`] to
+    /// execute requests, eg. one that has been setup with the proxy, timeout,
+    /// and TLS settings required for your environment
+    pub fn with_http_client(client: AClient) -> Self {
+        Self::from(client)
+    }
+
+    /// Creates a client that uses the specified HTTP protocol version(s)
+    pub fn with_http_version(version: super::HttpVersion) -> Result<Self, Error> {
+        use super::HttpVersion;
+
+        let builder = AClient::builder();
+        let builder = match version {
+            HttpVersion::Negotiate => builder,
+            HttpVersion::Http1Only => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+
+        Ok(Self::from(builder.build()?))
+    }
+
+    /// Adds an `X-Request-Id` header with a unique v4 UUID to every request
+    /// that doesn't already have one, so that requests can be correlated in
+    /// logs
+    pub fn with_request_ids(self) -> Self {
+        self.with_request_id_generator(super::uuid_request_id)
+    }
+
+    /// Adds an `X-Request-Id` header with an id produced by the specified
+    /// generator to every request that doesn't already have one
+    pub fn with_request_id_generator(
+        mut self,
+        generator: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.request_id = Some(Box::new(generator));
+        self
+    }
+
+    /// Sends the request and parses the response. If the request was sent
+    /// with an `X-Request-Id`, any error from sending it, or error status in
+    /// the response, includes the [`request_id`](Error::request_id)
+    pub async fn execute<Res>(&self, mut req: http::Request<bytes::Bytes>) -> Result<Res, Error>
+    where
+        Res: crate::ApiResponse<bytes::Bytes>,
+    {
+        let request_id = super::add_request_id(&mut req, self.request_id.as_ref());
+
+        self.send(req)
+            .await
+            .map_err(|err| err.with_request_id(request_id))
+    }
+
+    async fn send<Res>(&self, req: http::Request<bytes::Bytes>) -> Result<Res, Error>
     where
         Res: crate::ApiResponse<bytes::Bytes>,
     {
@@ -27,7 +83,135 @@ impl Client {
         let response = self.inner.execute(request).await?;
         let response = convert_response(response).await?;
 
-        Ok(Res::try_from_parts(response)?)
+        Res::try_from_parts(response)
+    }
+
+    /// Executes every request, eg. the chunked requests from
+    /// [`definitions::get`](crate::definitions::get), keeping as many in
+    /// flight at once as the [`ConcurrencyController`](super::ConcurrencyController)
+    /// allows. The latency or error of each request is recorded with the
+    /// controller, so the concurrency backs off when the API slows down or
+    /// starts rejecting requests, and the controller can be reused for later
+    /// batches to start from the limit it settled on.
+    ///
+    /// The results are returned in the same order as the requests, a failed
+    /// request doesn't prevent the remaining requests from being executed, so
+    /// that only the failed ones need to be retried.
+    pub async fn execute_concurrently<Res, I>(
+        &self,
+        requests: I,
+        controller: &mut super::ConcurrencyController,
+    ) -> Vec<Result<Res, Error>>
+    where
+        Res: crate::ApiResponse<bytes::Bytes>,
+        I: IntoIterator<Item = http::Request<bytes::Bytes>>,
+    {
+        use futures_util::stream::{FuturesUnordered, StreamExt};
+
+        let mut requests = requests.into_iter().enumerate();
+        let mut in_flight = FuturesUnordered::new();
+        let mut results = Vec::new();
+
+        loop {
+            while in_flight.len() < controller.limit() {
+                let Some((i, req)) = requests.next() else {
+                    break;
+                };
+
+                in_flight.push(async move {
+                    let start = std::time::Instant::now();
+                    let res = self.execute::<Res>(req).await;
+                    (i, start.elapsed(), res)
+                });
+            }
+
+            let Some((i, latency, res)) = in_flight.next().await else {
+                break;
+            };
+
+            match &res {
+                Ok(_) => controller.record_success(latency),
+                Err(err) => controller.record_error(err),
+            }
+
+            results.push((i, res));
+        }
+
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, res)| res).collect()
+    }
+
+    /// Finds every harvested revision of the specified component, following
+    /// the continuation token of each page of results until all of them have
+    /// been collected
+    ///
+    /// This is equivalent to [`Self::all_revisions_with`] using the default
+    /// [`GetOptions`](crate::definitions::GetOptions).
+    pub async fn all_revisions(
+        &self,
+        shape: crate::Shape,
+        provider: crate::Provider,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Result<Vec<crate::Coordinate>, Error> {
+        self.all_revisions_with(
+            &crate::definitions::GetOptions::default(),
+            shape,
+            provider,
+            namespace,
+            name,
+        )
+        .await
+    }
+
+    /// Finds every harvested revision of the specified component, the same as
+    /// [`Self::all_revisions`], but with each request configured by the
+    /// specified [`GetOptions`](crate::definitions::GetOptions)
+    ///
+    /// Fails if the API returns a continuation token that it has already
+    /// returned, as following it would loop forever.
+    pub async fn all_revisions_with(
+        &self,
+        options: &crate::definitions::GetOptions,
+        shape: crate::Shape,
+        provider: crate::Provider,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Result<Vec<crate::Coordinate>, Error> {
+        use crate::definitions::{find_with, FindResponse};
+
+        let mut coordinates = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut token = None;
+
+        loop {
+            let req = find_with(
+                options,
+                &shape,
+                &provider,
+                namespace,
+                name,
+                token.as_deref(),
+            );
+            let page: FindResponse = self.execute(req).await?;
+
+            coordinates.extend(page.coordinates);
+
+            let Some(next) = page.continuation_token else {
+                break;
+            };
+
+            if !seen.insert(next.clone()) {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "the continuation token '{}' was returned more than once",
+                    next
+                )));
+            }
+
+            token = Some(next);
+        }
+
+        Ok(coordinates)
     }
 }
 
@@ -0,0 +1,94 @@
+use crate::Error;
+use std::time::Duration;
+
+/// Adjusts the number of requests that should be in flight at once based on
+/// the observed latency and errors of the requests, as the definitions API can
+/// be extremely slow, and sending too many concurrent requests only makes it
+/// slower.
+///
+/// This uses an additive increase/multiplicative decrease (AIMD) strategy, ie.
+/// the limit is increased by 1 once a full limit's worth of requests have
+/// completed within the target latency, and halved whenever a request is
+/// slower than the target, times out, or is rate limited, always staying
+/// within the configured bounds. After the limit is halved, further signals
+/// are ignored until the requests that were already in flight have completed.
+///
+/// The async `Client::execute_concurrently` executes a batch of requests with
+/// a controller. Otherwise, eg. with the blocking client, callers should check
+/// the [`limit`](Self::limit) before starting a new request, and record the
+/// result of each request once it has completed.
+#[derive(Debug)]
+pub struct ConcurrencyController {
+    limit: usize,
+    min: usize,
+    max: usize,
+    target_latency: Duration,
+    /// The number of requests that have completed since the limit was last
+    /// increased
+    completed: usize,
+    /// The number of requests still to complete before the limit can be
+    /// decreased again
+    cooldown: usize,
+}
+
+impl ConcurrencyController {
+    /// Creates a controller that keeps the concurrency limit between `min` and
+    /// `max`, starting at `min`. Requests that take longer than the target
+    /// latency are treated as a signal that the API is overloaded.
+    pub fn new(min: usize, max: usize, target_latency: Duration) -> Self {
+        let min = std::cmp::max(min, 1);
+        let max = std::cmp::max(max, min);
+
+        Self {
+            limit: min,
+            min,
+            max,
+            target_latency,
+            completed: 0,
+            cooldown: 0,
+        }
+    }
+
+    /// The maximum number of requests that should currently be in flight
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Records a request that completed successfully with the specified latency
+    pub fn record_success(&mut self, latency: Duration) {
+        if latency > self.target_latency {
+            self.decrease();
+            return;
+        }
+
+        self.cooldown = self.cooldown.saturating_sub(1);
+        self.completed += 1;
+
+        if self.completed >= self.limit {
+            self.limit = std::cmp::min(self.limit + 1, self.max);
+            self.completed = 0;
+        }
+    }
+
+    /// Records a request that failed. Errors that are worth retrying, see
+    /// [`Error::is_retryable`], such as timeouts, rate limiting (429), and
+    /// server errors, are treated as a signal that the API is overloaded and
+    /// reduce the limit, other errors are not related to load so are ignored.
+    pub fn record_error(&mut self, error: &Error) {
+        if error.is_retryable() {
+            self.decrease();
+        }
+    }
+
+    fn decrease(&mut self) {
+        if self.cooldown > 0 {
+            self.cooldown -= 1;
+            return;
+        }
+
+        self.cooldown = self.limit;
+        self.limit = std::cmp::max(self.limit / 2, self.min);
+        self.completed = 0;
+    }
+}
@@ -6,11 +6,15 @@ use reqwest::blocking::Client as BClient;
 #[derive(Default)]
 pub struct Client {
     inner: BClient,
+    request_id: Option<super::RequestIdGenerator>,
 }
 
 impl From<BClient> for Client {
     fn from(o: BClient) -> Self {
-        Self { inner: o }
+        Self {
+            inner: o,
+            request_id: None,
+        }
     }
 }
 
@@ -19,7 +23,59 @@ impl Client {
         Self::default()
     }
 
-    pub fn execute<Res>(&self, req: http::Request<bytes::Bytes>) -> Result<Res, Error>
+    /// Creates a client that uses an externally configured
+    /// [`reqwest::blocking::Client`] to execute requests, eg. one that has been
+    /// setup with the proxy, timeout, and TLS settings required for your
+    /// environment
+    pub fn with_http_client(client: BClient) -> Self {
+        Self::from(client)
+    }
+
+    /// Creates a client that uses the specified HTTP protocol version(s)
+    pub fn with_http_version(version: super::HttpVersion) -> Result<Self, Error> {
+        use super::HttpVersion;
+
+        let builder = BClient::builder();
+        let builder = match version {
+            HttpVersion::Negotiate => builder,
+            HttpVersion::Http1Only => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+
+        Ok(Self::from(builder.build()?))
+    }
+
+    /// Adds an `X-Request-Id` header with a unique v4 UUID to every request
+    /// that doesn't already have one, so that requests can be correlated in
+    /// logs
+    pub fn with_request_ids(self) -> Self {
+        self.with_request_id_generator(super::uuid_request_id)
+    }
+
+    /// Adds an `X-Request-Id` header with an id produced by the specified
+    /// generator to every request that doesn't already have one
+    pub fn with_request_id_generator(
+        mut self,
+        generator: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.request_id = Some(Box::new(generator));
+        self
+    }
+
+    /// Sends the request and parses the response. If the request was sent
+    /// with an `X-Request-Id`, any error from sending it, or error status in
+    /// the response, includes the [`request_id`](Error::request_id)
+    pub fn execute<Res>(&self, mut req: http::Request<bytes::Bytes>) -> Result<Res, Error>
+    where
+        Res: crate::ApiResponse<bytes::Bytes>,
+    {
+        let request_id = super::add_request_id(&mut req, self.request_id.as_ref());
+
+        self.send(req)
+            .map_err(|err| err.with_request_id(request_id))
+    }
+
+    fn send<Res>(&self, req: http::Request<bytes::Bytes>) -> Result<Res, Error>
     where
         Res: crate::ApiResponse<bytes::Bytes>,
     {
@@ -29,6 +85,78 @@ impl Client {
 
         Res::try_from_parts(response)
     }
+
+    /// Finds every harvested revision of the specified component, following
+    /// the continuation token of each page of results until all of them have
+    /// been collected
+    ///
+    /// This is equivalent to [`Self::all_revisions_with`] using the default
+    /// [`GetOptions`](crate::definitions::GetOptions).
+    pub fn all_revisions(
+        &self,
+        shape: crate::Shape,
+        provider: crate::Provider,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Result<Vec<crate::Coordinate>, Error> {
+        self.all_revisions_with(
+            &crate::definitions::GetOptions::default(),
+            shape,
+            provider,
+            namespace,
+            name,
+        )
+    }
+
+    /// Finds every harvested revision of the specified component, the same as
+    /// [`Self::all_revisions`], but with each request configured by the
+    /// specified [`GetOptions`](crate::definitions::GetOptions)
+    ///
+    /// Fails if the API returns a continuation token that it has already
+    /// returned, as following it would loop forever.
+    pub fn all_revisions_with(
+        &self,
+        options: &crate::definitions::GetOptions,
+        shape: crate::Shape,
+        provider: crate::Provider,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Result<Vec<crate::Coordinate>, Error> {
+        use crate::definitions::{find_with, FindResponse};
+
+        let mut coordinates = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut token = None;
+
+        loop {
+            let req = find_with(
+                options,
+                &shape,
+                &provider,
+                namespace,
+                name,
+                token.as_deref(),
+            );
+            let page: FindResponse = self.execute(req)?;
+
+            coordinates.extend(page.coordinates);
+
+            let Some(next) = page.continuation_token else {
+                break;
+            };
+
+            if !seen.insert(next.clone()) {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "the continuation token '{}' was returned more than once",
+                    next
+                )));
+            }
+
+            token = Some(next);
+        }
+
+        Ok(coordinates)
+    }
 }
 
 /// Converts a vanilla [`http::Request`] into a [`reqwest::Request`]
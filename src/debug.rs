@@ -0,0 +1,46 @@
+//! Helpers for debugging requests to the API
+
+use bytes::Bytes;
+
+/// Headers whose values are replaced when rendering requests, as they contain
+/// credentials
+const REDACTED_HEADERS: &[http::HeaderName] = &[
+    http::header::AUTHORIZATION,
+    http::header::PROXY_AUTHORIZATION,
+    http::header::COOKIE,
+];
+
+/// Renders the request as a `curl` command that can be run in a shell to
+/// reproduce it, eg. when reporting an issue with the API. The values of
+/// headers that contain credentials, such as `Authorization`, are redacted.
+pub fn to_curl(req: &http::Request<Bytes>) -> String {
+    let mut curl = format!(
+        "curl -X {} {}",
+        req.method(),
+        shell_quote(&req.uri().to_string())
+    );
+
+    for (name, value) in req.headers() {
+        let value = if REDACTED_HEADERS.contains(name) {
+            "<redacted>"
+        } else {
+            value.to_str().unwrap_or("<non-utf8>")
+        };
+
+        curl.push_str(" -H ");
+        curl.push_str(&shell_quote(&format!("{}: {}", name, value)));
+    }
+
+    if !req.body().is_empty() {
+        curl.push_str(" -d ");
+        curl.push_str(&shell_quote(&String::from_utf8_lossy(req.body())));
+    }
+
+    curl
+}
+
+/// Quotes a string so that it is passed verbatim as a single argument in a
+/// POSIX shell
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
@@ -2,37 +2,133 @@ use crate::{ApiResponse, Error};
 use bytes::Bytes;
 use http::Request;
 use serde::Deserialize;
-use std::{collections::BTreeMap, convert::TryFrom, fmt};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    fmt,
+};
 
 /// The coordinates of a definition
+///
+/// The namespace and name are always decoded, the same as the fields of a
+/// [`Coordinate`](crate::Coordinate), regardless of whether the response
+/// contained them percent-encoded, eg. the `github.com%2fstretchr` namespace of
+/// a Go module is `github.com/stretchr`, and a `-` namespace is the same as no
+/// namespace
 #[derive(Deserialize, Debug)]
+#[serde(from = "RawDefCoords")]
 pub struct DefCoords {
-    #[serde(rename = "type")]
     pub shape: crate::Shape,
     pub provider: crate::Provider,
+    /// The namespace of the component, if it has one, eg. the vendor of a
+    /// composer package
+    pub namespace: Option<String>,
     pub name: String,
     pub revision: crate::CoordVersion,
+    /// A link to the component, only present in some responses
+    pub url: Option<String>,
+}
+
+/// The coordinates of a definition as they appear in the response
+#[derive(Deserialize)]
+struct RawDefCoords {
+    #[serde(rename = "type")]
+    shape: crate::Shape,
+    provider: crate::Provider,
+    #[serde(default)]
+    namespace: Option<String>,
+    name: String,
+    revision: crate::CoordVersion,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+impl From<RawDefCoords> for DefCoords {
+    fn from(raw: RawDefCoords) -> Self {
+        // Keep the segment as is if it isn't validly encoded rather than
+        // failing the entire definition
+        let decode =
+            |segment: String| crate::decode_component(&raw.shape, &segment).unwrap_or(segment);
+
+        Self {
+            namespace: raw.namespace.filter(|ns| ns != "-").map(decode),
+            name: decode(raw.name),
+            revision: revision_for_shape(&raw.shape, raw.revision),
+            shape: raw.shape,
+            provider: raw.provider,
+            url: raw.url,
+        }
+    }
+}
+
+impl DefCoords {
+    /// Creates the coordinate for the component the definition is for, the
+    /// same as the `From<DefCoords>` impl of [`Coordinate`](crate::Coordinate)
+    /// but without consuming the coordinates
+    pub fn to_coordinate(&self) -> crate::Coordinate {
+        crate::Coordinate {
+            shape: self.shape.clone(),
+            provider: self.provider.clone(),
+            namespace: self.namespace.clone(),
+            name: self.name.clone(),
+            version: revision_for_shape(&self.shape, self.revision.clone()),
+            curation_pr: None,
+        }
+    }
+}
+
+/// Creates the coordinate for the component the definition is for, with no
+/// curation PR, as that is not included in the coordinates of a definition
+impl From<DefCoords> for crate::Coordinate {
+    fn from(dc: DefCoords) -> Self {
+        Self {
+            version: revision_for_shape(&dc.shape, dc.revision),
+            shape: dc.shape,
+            provider: dc.provider,
+            namespace: dc.namespace,
+            name: dc.name,
+            curation_pr: None,
+        }
+    }
+}
+
+/// The revision of definition coordinates is deserialized without knowing the
+/// shape, so git commit shas need to be parsed again
+#[inline]
+fn revision_for_shape(shape: &crate::Shape, revision: crate::CoordVersion) -> crate::CoordVersion {
+    match revision {
+        crate::CoordVersion::Any(rev) => crate::CoordVersion::for_shape(shape, &rev),
+        rev => rev,
+    }
 }
 
+/// Displays the coordinates the same as the equivalent [`Coordinate`](crate::Coordinate)
 impl fmt::Display for DefCoords {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}/{}/{}/{}",
-            self.shape.as_str(),
-            self.provider.as_str(),
-            self.name,
-            self.revision,
-        )
+        let path = crate::CoordPath {
+            shape: &self.shape,
+            provider: &self.provider,
+            namespace: self.namespace.as_deref(),
+            name: &self.name,
+            version: &self.revision,
+        };
+
+        write!(f, "{}", path)
     }
 }
 
-#[derive(Deserialize, PartialEq, Debug)]
+/// The hashes of a component or file, which hashes are present depends on the
+/// tools that were used to harvest them
+#[derive(Deserialize, PartialEq, Default, Debug)]
 pub struct Hashes {
+    /// The md5 hash of a file
+    pub md5: Option<String>,
     /// The sha-1 hash of a file
-    pub sha1: String,
+    pub sha1: Option<String>,
     /// The sha-256 hash of a file
     pub sha256: Option<String>,
+    /// The sha-512 hash of a file
+    pub sha512: Option<String>,
 }
 
 #[derive(Deserialize, PartialEq, Debug)]
@@ -46,19 +142,51 @@ pub struct Scores {
 pub struct SourceLocation {
     pub r#type: String,
     pub provider: String,
+    /// The namespace of the source, this is empty if the source doesn't have
+    /// one, eg. Debian source packages
+    #[serde(default)]
     pub namespace: String,
     pub name: String,
     pub revision: String,
     pub url: String,
 }
 
-#[derive(PartialEq, Debug)]
+impl SourceLocation {
+    /// Converts the source location into a coordinate, eg. a
+    /// `git/github/dtolnay/syn/<sha>` coordinate for a crate whose source is
+    /// on GitHub. Returns `None` if the shape or provider isn't supported.
+    pub fn to_coordinate(&self) -> Option<crate::Coordinate> {
+        let namespace = match self.namespace.as_str() {
+            "" | "-" => None,
+            ns => Some(ns.to_owned()),
+        };
+
+        let shape = self.r#type.parse().ok()?;
+
+        Some(crate::Coordinate {
+            version: crate::CoordVersion::for_shape(&shape, &self.revision),
+            shape,
+            provider: self.provider.parse().ok()?,
+            namespace,
+            name: self.name.clone(),
+            curation_pr: None,
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct Date {
     pub year: u32,
     pub month: u8,
     pub day: u8,
 }
 
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
 /// Parses a [`Date`] from a string, clearly-defined uses a `YYYY-MM-DD` format
 fn date<'de, D>(deserializer: D) -> Result<Date, D::Error>
 where
@@ -88,6 +216,15 @@ where
     Ok(Date { year, month, day })
 }
 
+/// Deserializes a value that may be `null`, in which case the default is used
+fn null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Description {
@@ -101,6 +238,7 @@ pub struct Description {
     /// Urls associated with the component, eg crates.io components will have
     /// the crates.io url, the version specific crates.io url, and the crates.io
     /// download url
+    #[serde(default, deserialize_with = "null_default")]
     pub urls: BTreeMap<String, String>,
     /// Actually unsure how these hashes are calculated
     pub hashes: Hashes,
@@ -111,9 +249,13 @@ pub struct Description {
     /// Scores for the component
     pub tool_score: Scores,
     pub score: Scores,
+    /// Whether the component has been marked as deprecated in its registry,
+    /// this is only present for registries that support deprecation
+    #[serde(default)]
+    pub deprecated: Option<bool>,
 }
 
-#[derive(Deserialize, PartialEq, Debug)]
+#[derive(PartialEq, Debug)]
 pub struct LicenseScore {
     pub total: u32,
     pub declared: u32,
@@ -123,6 +265,44 @@ pub struct LicenseScore {
     pub texts: u32,
 }
 
+// Some responses wrap the score in an additional `effective` object, so accept
+// either form
+impl<'de> Deserialize<'de> for LicenseScore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Raw {
+            total: u32,
+            declared: u32,
+            discovered: u32,
+            consistency: u32,
+            spdx: u32,
+            texts: u32,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Score {
+            Flat(Raw),
+            Wrapped { effective: Raw },
+        }
+
+        let (Score::Flat(raw) | Score::Wrapped { effective: raw }) =
+            Score::deserialize(deserializer)?;
+
+        Ok(Self {
+            total: raw.total,
+            declared: raw.declared,
+            discovered: raw.discovered,
+            consistency: raw.consistency,
+            spdx: raw.spdx,
+            texts: raw.texts,
+        })
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Attribution {
     /// The number of files that had no attribution
@@ -150,6 +330,28 @@ pub struct Facet {
     pub files: u32,
 }
 
+impl Facet {
+    /// The fraction of files in the facet that had license information that
+    /// could be identified, or 0 if there are no files
+    pub fn license_coverage(&self) -> f32 {
+        if self.files == 0 {
+            return 0.0;
+        }
+
+        self.files.saturating_sub(self.discovered.unknown) as f32 / self.files as f32
+    }
+
+    /// The fraction of files in the facet that had attribution information
+    /// that could be identified, or 0 if there are no files
+    pub fn attribution_coverage(&self) -> f32 {
+        if self.files == 0 {
+            return 0.0;
+        }
+
+        self.files.saturating_sub(self.attribution.unknown) as f32 / self.files as f32
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Facets {
     /// The only facet I have seen, don't know if there will be more in the future
@@ -171,6 +373,175 @@ pub struct License {
     pub score: LicenseScore,
 }
 
+/// Parses the natures of a [`File`], which are normally an array of strings,
+/// but some harvest tools emit them as a single comma-separated string instead
+fn natures<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Natures {
+        List(Vec<String>),
+        Csv(String),
+    }
+
+    Ok(match Natures::deserialize(deserializer)? {
+        Natures::List(natures) => natures,
+        Natures::Csv(natures) => natures
+            .split(',')
+            .map(str::trim)
+            .filter(|nature| !nature.is_empty())
+            .map(String::from)
+            .collect(),
+    })
+}
+
+/// The identifier prefixes of the copyleft license families recognized by
+/// [`License::is_copyleft`], eg. `GPL` matches `GPL-2.0-only` and
+/// `GPL-3.0-or-later`
+#[cfg(feature = "spdx")]
+pub const COPYLEFT_FAMILIES: &[&str] = &["GPL", "LGPL", "AGPL", "MPL", "EPL"];
+
+impl License {
+    /// The fraction of files in the core facet that had license information
+    /// that could be identified, see [`Facet::license_coverage`]
+    #[inline]
+    pub fn license_coverage(&self) -> f32 {
+        self.facets.core.license_coverage()
+    }
+}
+
+#[cfg(feature = "spdx")]
+impl License {
+    /// Parses the declared license expression
+    fn declared_expression(&self) -> Option<spdx::Expression> {
+        spdx::Expression::parse_mode(&self.declared, spdx::ParseMode::LAX).ok()
+    }
+
+    /// Gets the license identifier of a requirement as it was written in the
+    /// declared expression, as the parsed requirement normalizes GNU licenses,
+    /// eg. `GPL-3.0-only` becomes `GPL-3.0`
+    fn declared_license(&self, er: &spdx::expression::ExpressionReq) -> &str {
+        &self.declared[er.span.start as usize..er.span.end as usize]
+    }
+
+    /// Gets the most restrictive license in the declared license expression,
+    /// according to the supplied ranking of license identifiers, ordered from
+    /// most to least restrictive.
+    ///
+    /// Licenses in the expression that don't appear in the ranking are ignored,
+    /// so `None` is returned if the expression can't be parsed or none of its
+    /// licenses are in the ranking.
+    pub fn most_restrictive(&self, ranking: &[&str]) -> Option<String> {
+        let expr = self.declared_expression()?;
+
+        expr.requirements()
+            .filter_map(|er| {
+                let license = self.declared_license(er);
+                let rank = ranking.iter().position(|r| *r == license)?;
+                Some((rank, license))
+            })
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, license)| license.to_owned())
+    }
+
+    /// Checks if any of the licenses in the declared license expression are
+    /// from one of the [`COPYLEFT_FAMILIES`]
+    pub fn is_copyleft(&self) -> bool {
+        self.declared_expression().is_some_and(|expr| {
+            expr.requirements().any(|er| {
+                let license = self.declared_license(er);
+                COPYLEFT_FAMILIES.iter().any(|family| {
+                    license
+                        .strip_prefix(family)
+                        .is_some_and(|version| version.starts_with('-'))
+                })
+            })
+        })
+    }
+
+    /// Gets the declared license expression with any deprecated SPDX license
+    /// identifiers upgraded to their current forms, eg. `GPL-2.0` becomes
+    /// `GPL-2.0-only` and `GPL-2.0+` becomes `GPL-2.0-or-later`.
+    ///
+    /// The mapping is taken from the deprecated identifiers in the
+    /// [SPDX license list](https://spdx.org/licenses/#deprecated), see
+    /// [`DEPRECATED_GNU_IDS`]. If the expression can't be parsed it is
+    /// returned unchanged.
+    pub fn normalized_declared(&self) -> String {
+        let Some(expr) = self.declared_expression() else {
+            return self.declared.clone();
+        };
+
+        let mut normalized = self.declared.clone();
+
+        // Replace from the end so that the spans of earlier requirements are
+        // still valid after each replacement
+        let mut reqs: Vec<_> = expr.requirements().collect();
+        reqs.sort_by_key(|er| std::cmp::Reverse(er.span.start));
+
+        for er in reqs {
+            let license = self.declared_license(er);
+            if !DEPRECATED_GNU_IDS.contains(&license) {
+                continue;
+            }
+
+            let start = er.span.start as usize;
+            let mut end = er.span.end as usize;
+
+            let replacement = if self.declared[end..].starts_with('+') {
+                end += 1;
+                format!("{}-or-later", license)
+            } else {
+                format!("{}-only", license)
+            };
+
+            normalized.replace_range(start..end, &replacement);
+        }
+
+        normalized
+    }
+}
+
+/// The deprecated SPDX identifiers for GNU licenses, which have been replaced
+/// by `-only` and `-or-later` variants, as the plain identifiers were
+/// ambiguous about whether later versions of the license could be used
+#[cfg(feature = "spdx")]
+pub const DEPRECATED_GNU_IDS: &[&str] = &[
+    "AGPL-1.0", "AGPL-3.0", "GFDL-1.1", "GFDL-1.2", "GFDL-1.3", "GPL-1.0", "GPL-2.0", "GPL-3.0",
+    "LGPL-2.0", "LGPL-2.1", "LGPL-3.0",
+];
+
+impl License {
+    /// Checks if the license expression discovered for a file is covered by
+    /// the declared license expression, ie. every license in it also appears
+    /// in the declared expression
+    #[cfg(feature = "spdx")]
+    fn covers(&self, license: &str) -> bool {
+        match (
+            self.declared_expression(),
+            spdx::Expression::parse_mode(license, spdx::ParseMode::LAX),
+        ) {
+            (Some(declared), Ok(discovered)) => discovered.requirements().all(|file_req| {
+                declared.requirements().any(|declared_req| {
+                    declared_req.req.license == file_req.req.license
+                        && declared_req.req.exception == file_req.req.exception
+                })
+            }),
+            _ => self.declared.contains(license),
+        }
+    }
+
+    /// Checks if the license expression discovered for a file is covered by
+    /// the declared license expression, without the `spdx` feature this just
+    /// checks if the declared expression contains it
+    #[cfg(not(feature = "spdx"))]
+    fn covers(&self, license: &str) -> bool {
+        self.declared.contains(license)
+    }
+}
+
 /// A single file that was crawled when the definition was harvested
 #[derive(Deserialize, Debug)]
 pub struct File {
@@ -186,7 +557,7 @@ pub struct File {
     /// "Natures" determined for the file. Unsure how many of them there are
     /// but in practice I have only seen `license` so this should probably be
     /// made into an enum at some point
-    #[serde(default)]
+    #[serde(default, deserialize_with = "natures")]
     pub natures: Vec<String>,
 }
 
@@ -196,6 +567,18 @@ pub struct TopLevelScore {
     pub tool: u8,
 }
 
+/// Metadata about the definition itself
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    /// The version of the schema the definition was computed with
+    pub schema_version: String,
+    /// The RFC3339 timestamp of when the definition was last computed. This
+    /// is kept as the raw string as the crate doesn't depend on a datetime
+    /// crate that could parse it
+    pub updated: String,
+}
+
 #[derive(Debug)]
 pub struct Definition {
     /// The specific coordinates the definition pertains to
@@ -207,6 +590,308 @@ pub struct Definition {
     /// All of the files that were crawled during the harvest of the component
     pub files: Vec<File>,
     pub scores: TopLevelScore,
+    /// Metadata about the definition, if it was present and valid in the
+    /// response
+    pub meta: Option<Meta>,
+}
+
+impl Definition {
+    /// Returns true if the component has actually been harvested, as the API
+    /// still returns a definition for components that haven't been
+    #[inline]
+    pub fn is_harvested(&self) -> bool {
+        self.described.is_some()
+    }
+
+    /// Gets the coordinate of the location the component was harvested from,
+    /// eg. to display the GitHub repository of a crate alongside it. Returns
+    /// `None` if there is no source location or its provider isn't supported.
+    pub fn source_coordinate(&self) -> Option<crate::Coordinate> {
+        self.described
+            .as_ref()?
+            .source_location
+            .as_ref()?
+            .to_coordinate()
+    }
+
+    /// Returns true if the location the component was harvested from is known,
+    /// components without one, eg. crates published without a `repository`,
+    /// may need to have their source mapped manually
+    #[inline]
+    pub fn has_source(&self) -> bool {
+        self.described
+            .as_ref()
+            .is_some_and(|desc| desc.source_location.is_some())
+    }
+
+    /// Returns true if at least `min_ratio` of the files in the core facet have
+    /// known attribution, eg. for policies that require copyright holders to
+    /// be identified. Components that haven't been harvested never meet it.
+    pub fn meets_attribution(&self, min_ratio: f32) -> bool {
+        self.is_harvested()
+            && self
+                .licensed
+                .as_ref()
+                .is_some_and(|lic| lic.facets.core.attribution_coverage() >= min_ratio)
+    }
+
+    /// Returns true if the component has been marked as deprecated by its
+    /// registry, which may indicate that it is no longer maintained and should
+    /// be replaced
+    #[inline]
+    pub fn is_deprecated(&self) -> bool {
+        self.described
+            .as_ref()
+            .and_then(|desc| desc.deprecated)
+            .unwrap_or_default()
+    }
+
+    /// Checks if the location the component was harvested from appears to be
+    /// for the same component as the package, eg. a crate whose source points
+    /// at a repository with a different name may warrant further inspection.
+    ///
+    /// The names are compared case-insensitively, and if the source is the
+    /// same shape as the package, eg. both are git, the providers must also be
+    /// the same. Returns `None` if there is no source location.
+    pub fn source_matches_package(&self) -> Option<bool> {
+        let source = self.described.as_ref()?.source_location.as_ref()?;
+        let coords = &self.coordinates;
+
+        let same_provider =
+            source.r#type != coords.shape.as_str() || source.provider == coords.provider.as_str();
+
+        Some(same_provider && source.name.eq_ignore_ascii_case(&coords.name))
+    }
+
+    /// Exports the definition as the package information section of an SPDX
+    /// tag-value document. Any fields that are unknown because the component
+    /// hasn't been harvested are set to `NOASSERTION`.
+    #[cfg(feature = "spdx-doc")]
+    pub fn to_spdx_tag_value(&self) -> String {
+        const NOASSERTION: &str = "NOASSERTION";
+
+        let license = self
+            .licensed
+            .as_ref()
+            .map_or(NOASSERTION, |lic| lic.declared.as_str());
+        let download = self
+            .described
+            .as_ref()
+            .and_then(|desc| {
+                desc.urls
+                    .get("download")
+                    .or_else(|| desc.source_location.as_ref().map(|sl| &sl.url))
+            })
+            .map_or(NOASSERTION, |url| url.as_str());
+
+        format!(
+            "PackageName: {}\nPackageVersion: {}\nPackageLicenseConcluded: {}\nPackageDownloadLocation: {}\n",
+            self.coordinates.name, self.coordinates.revision, license, download,
+        )
+    }
+
+    /// Checks if the specified tool, eg. `scancode`, was used to harvest the
+    /// component, and if so, whether the version used was older than
+    /// `min_version`, which may indicate the definition is stale and the
+    /// component should be queued for harvesting again.
+    ///
+    /// If the tool was used multiple times, the newest version is compared.
+    /// Returns `None` if the tool wasn't used, or if neither its version nor
+    /// `min_version` are valid semver versions.
+    pub fn tool_version_older_than(&self, tool: &str, min_version: &str) -> Option<bool> {
+        let min_version = semver::Version::parse(min_version).ok()?;
+
+        let newest = self
+            .described
+            .as_ref()?
+            .tools
+            .iter()
+            .filter_map(|t| {
+                let (name, version) = t.split_once('/')?;
+                if name != tool {
+                    return None;
+                }
+
+                semver::Version::parse(version).ok()
+            })
+            .max()?;
+
+        Some(newest < min_version)
+    }
+
+    /// Removes every file that isn't relevant for attribution, ie. files that
+    /// have neither a `license` or `notice` nature, nor any attributions,
+    /// which can substantially reduce the size of cached definitions
+    pub fn retain_relevant_files(&mut self) {
+        self.files.retain(|file| {
+            !file.attributions.is_empty()
+                || file
+                    .natures
+                    .iter()
+                    .any(|nature| nature == "license" || nature == "notice")
+        });
+    }
+
+    /// Combines the declared license expression with every license expression
+    /// discovered in the core facet into a single expression that requires
+    /// all of them, for when compliance needs to be as strict as possible, eg.
+    /// declared `MIT` and discovered `MIT` and `Apache-2.0` becomes
+    /// `MIT AND Apache-2.0`.
+    ///
+    /// Expressions that only combine licenses with `AND` are split into their
+    /// individual licenses so that duplicate licenses are only included once,
+    /// and any remaining compound expressions are parenthesized. `NOASSERTION`
+    /// is ignored, so `None` is returned if the component hasn't been harvested
+    /// or has no known licenses.
+    pub fn conservative_license(&self) -> Option<String> {
+        let licensed = self.licensed.as_ref()?;
+
+        let mut expressions = Vec::<&str>::new();
+        for expr in std::iter::once(&licensed.declared)
+            .chain(&licensed.facets.core.discovered.expressions)
+            .flat_map(|expr| {
+                let expr = expr.trim();
+                if expr.contains(" OR ") || expr.contains('(') {
+                    vec![expr]
+                } else {
+                    expr.split(" AND ").map(str::trim).collect()
+                }
+            })
+        {
+            if expr.is_empty() || expr == "NOASSERTION" || expressions.contains(&expr) {
+                continue;
+            }
+
+            expressions.push(expr);
+        }
+
+        if expressions.is_empty() {
+            return None;
+        }
+
+        if expressions.len() == 1 {
+            return Some(expressions[0].to_owned());
+        }
+
+        let combined = expressions
+            .into_iter()
+            .map(|expr| {
+                if expr.contains(' ') && !(expr.starts_with('(') && expr.ends_with(')')) {
+                    format!("({})", expr)
+                } else {
+                    expr.to_owned()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+
+        Some(combined)
+    }
+
+    /// Gets the files whose discovered license isn't covered by the declared
+    /// license, eg. a file licensed as `GPL-3.0-only` in a component declared
+    /// as `MIT`.
+    ///
+    /// With the `spdx` feature, a file's license is covered if every license
+    /// in its expression is also in the declared expression, otherwise the
+    /// declared expression simply has to contain the file's license. Files
+    /// without a license, or with `NOASSERTION`, are ignored.
+    pub fn license_conflicts(&self) -> Vec<&File> {
+        let Some(licensed) = &self.licensed else {
+            return Vec::new();
+        };
+
+        self.files
+            .iter()
+            .filter(|file| {
+                file.license
+                    .as_deref()
+                    .is_some_and(|lic| lic != "NOASSERTION" && !licensed.covers(lic))
+            })
+            .collect()
+    }
+
+    /// Summarizes the licenses discovered for each file by the directory they
+    /// are in, truncated to the first `depth` components, eg. with a depth of
+    /// 1 `src/lib.rs` and `src/sub/mod.rs` are both grouped under `src`. Files
+    /// in the root of the component are grouped under an empty string.
+    ///
+    /// This is useful for finding eg. vendored code that has a different
+    /// license from the rest of the component.
+    pub fn licenses_by_directory(&self, depth: usize) -> BTreeMap<String, BTreeSet<String>> {
+        let mut dirs = BTreeMap::<String, BTreeSet<String>>::new();
+
+        for file in &self.files {
+            let Some(license) = &file.license else {
+                continue;
+            };
+
+            let dir = file
+                .path
+                .parent()
+                .map(|parent| {
+                    parent
+                        .components()
+                        .take(depth)
+                        .map(|c| c.as_str())
+                        .collect::<Vec<_>>()
+                        .join("/")
+                })
+                .unwrap_or_default();
+
+            dirs.entry(dir).or_default().insert(license.clone());
+        }
+
+        dirs
+    }
+}
+
+/// A flattened summary of a [`Definition`], eg. for a row in a CSV file or table
+#[derive(serde::Serialize, PartialEq, Debug)]
+pub struct LicenseReportRow {
+    /// The coordinates of the definition
+    pub coordinate: String,
+    /// The declared license expression, if the component has been harvested
+    pub declared_license: Option<String>,
+    /// The effective score of the definition
+    pub effective_score: u8,
+    /// The date the component was released, in `YYYY-MM-DD` format
+    pub release_date: Option<String>,
+    /// The url of the location the component was harvested from
+    pub source_url: Option<String>,
+}
+
+impl<'d> From<&'d Definition> for LicenseReportRow {
+    fn from(def: &'d Definition) -> Self {
+        Self {
+            coordinate: def.coordinates.to_string(),
+            declared_license: def.licensed.as_ref().map(|lic| lic.declared.clone()),
+            effective_score: def.scores.effective,
+            release_date: def
+                .described
+                .as_ref()
+                .map(|desc| desc.release_date.to_string()),
+            source_url: def
+                .described
+                .as_ref()
+                .and_then(|desc| desc.source_location.as_ref())
+                .map(|sl| sl.url.clone()),
+        }
+    }
+}
+
+/// Writes the definitions as a CSV file, with a header followed by one
+/// [`LicenseReportRow`] per definition, eg. to hand to auditors
+#[cfg(feature = "csv")]
+pub fn to_csv<W: std::io::Write>(defs: &[Definition], w: W) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(w);
+
+    for def in defs {
+        writer.serialize(LicenseReportRow::from(def))?;
+    }
+
+    writer.flush().map_err(csv::Error::from)?;
+    Ok(())
 }
 
 // Somewhat annoyingly, instead of returning null or some kind of error if a
@@ -237,6 +922,7 @@ impl<'de> serde::Deserialize<'de> for Definition {
                 let mut described = None;
                 let mut licensed = None;
                 let mut files = Vec::new();
+                let mut meta = None;
                 let mut scores = TopLevelScore {
                     effective: 0,
                     tool: 0,
@@ -256,8 +942,11 @@ impl<'de> serde::Deserialize<'de> for Definition {
                                 return Err(de::Error::duplicate_field("described"));
                             }
 
-                            // Just disregard errors and set it to null
-                            let desc: Option<Description> = map.next_value().ok();
+                            // Just disregard errors and set it to null, note the
+                            // value is buffered first so that an error doesn't
+                            // leave the deserializer in a bad state
+                            let desc: serde_json::Value = map.next_value()?;
+                            let desc = Description::deserialize(&desc).ok();
 
                             described = Some(desc);
                         }
@@ -266,8 +955,11 @@ impl<'de> serde::Deserialize<'de> for Definition {
                                 return Err(de::Error::duplicate_field("licensed"));
                             }
 
-                            // Just disregard errors and set it to null
-                            let lic: Option<License> = map.next_value().ok();
+                            // Just disregard errors and set it to null, note the
+                            // value is buffered first so that an error doesn't
+                            // leave the deserializer in a bad state
+                            let lic: serde_json::Value = map.next_value()?;
+                            let lic = License::deserialize(&lic).ok();
 
                             licensed = Some(lic);
                         }
@@ -281,7 +973,22 @@ impl<'de> serde::Deserialize<'de> for Definition {
                         "scores" => {
                             scores = map.next_value()?;
                         }
-                        _ => { /* just ignore unknown fields */ }
+                        "_meta" => {
+                            if meta.is_some() {
+                                return Err(de::Error::duplicate_field("_meta"));
+                            }
+
+                            // Metadata is informational, so disregard errors
+                            // the same as the description and license
+                            let m: serde_json::Value = map.next_value()?;
+                            let m = Meta::deserialize(&m).ok();
+
+                            meta = Some(m);
+                        }
+                        _ => {
+                            // just ignore unknown fields
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
                     }
                 }
 
@@ -296,25 +1003,385 @@ impl<'de> serde::Deserialize<'de> for Definition {
                     licensed,
                     files,
                     scores,
+                    meta: meta.flatten(),
                 })
             }
         }
 
-        const FIELDS: &[&str] = &["coordinates", "described", "licensed", "files", "scores"];
+        const FIELDS: &[&str] = &[
+            "coordinates",
+            "described",
+            "licensed",
+            "files",
+            "scores",
+            "_meta",
+        ];
         deserializer.deserialize_struct("Definition", FIELDS, DefVisitor)
     }
 }
 
+/// A [`Definition`] along with the raw JSON it was deserialized from, useful
+/// for inspecting data that doesn't fit the expected schema without having to
+/// request the definition again
+#[derive(Debug)]
+pub struct DefinitionWithRaw {
+    /// The parsed definition
+    pub definition: Definition,
+    /// The JSON the definition was parsed from
+    pub raw: serde_json::Value,
+}
+
+impl<'de> serde::Deserialize<'de> for DefinitionWithRaw {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let raw = serde_json::Value::deserialize(deserializer)?;
+        let definition = Definition::deserialize(&raw).map_err(serde::de::Error::custom)?;
+
+        Ok(Self { definition, raw })
+    }
+}
+
+/// The differences between two sets of coordinates
+#[derive(Default, Debug)]
+pub struct CoordinateDiff {
+    /// Components that are only in the new set
+    pub added: Vec<crate::Coordinate>,
+    /// Components that are only in the old set
+    pub removed: Vec<crate::Coordinate>,
+    /// Components that are in both sets, but with a different revision, as
+    /// `(old, new)` pairs
+    pub changed: Vec<(crate::Coordinate, crate::Coordinate)>,
+}
+
+/// Compares two sets of coordinates, eg. the dependencies of a project before
+/// and after an upgrade, returning the components that were added, removed,
+/// or changed to a different revision
+pub fn coordinate_diff(old: &[crate::Coordinate], new: &[crate::Coordinate]) -> CoordinateDiff {
+    let mut removed: Vec<_> = old.iter().filter(|o| !new.contains(o)).collect();
+    let mut diff = CoordinateDiff::default();
+
+    for n in new.iter().filter(|n| !old.contains(n)) {
+        match removed.iter().position(|o| o.is_same_component(n)) {
+            Some(i) => diff.changed.push((removed.remove(i).clone(), n.clone())),
+            None => diff.added.push(n.clone()),
+        }
+    }
+
+    diff.removed = removed.into_iter().cloned().collect();
+    diff
+}
+
+/// Removes duplicate definitions for the same coordinates, eg. when merging
+/// responses from multiple sources, keeping the one with the highest effective
+/// score. If the scores are tied, the one with the most files is kept, and if
+/// those are also tied, the first one is kept. The order of the definitions
+/// is otherwise preserved.
+pub fn dedup_best(defs: Vec<Definition>) -> Vec<Definition> {
+    let mut best = Vec::<Definition>::with_capacity(defs.len());
+    let mut indices = BTreeMap::new();
+
+    for def in defs {
+        match indices.entry(def.coordinates.to_string()) {
+            std::collections::btree_map::Entry::Vacant(entry) => {
+                entry.insert(best.len());
+                best.push(def);
+            }
+            std::collections::btree_map::Entry::Occupied(entry) => {
+                let existing = &mut best[*entry.get()];
+                if (def.scores.effective, def.files.len())
+                    > (existing.scores.effective, existing.files.len())
+                {
+                    *existing = def;
+                }
+            }
+        }
+    }
+
+    best
+}
+
+/// Gets the definitions that don't have a source location, see
+/// [`Definition::has_source`]
+pub fn without_source(defs: &[Definition]) -> Vec<&Definition> {
+    defs.iter().filter(|def| !def.has_source()).collect()
+}
+
+/// Summary statistics for the definitions of a single ecosystem, see
+/// [`ecosystem_stats`]
+#[derive(Default, PartialEq, Debug)]
+pub struct EcosystemStats {
+    /// The number of definitions
+    pub count: usize,
+    /// The number of definitions that have been harvested
+    pub harvested: usize,
+    /// The mean effective score of all of the definitions
+    pub mean_score: f32,
+}
+
+/// Groups the definitions by their shape and provider, eg. to summarize the
+/// health of dependencies from each ecosystem in a dashboard
+pub fn ecosystem_stats(
+    defs: &[Definition],
+) -> BTreeMap<(crate::Shape, crate::Provider), EcosystemStats> {
+    let mut totals = BTreeMap::<_, (EcosystemStats, u64)>::new();
+
+    for def in defs {
+        let (stats, total_score) = totals
+            .entry((
+                def.coordinates.shape.clone(),
+                def.coordinates.provider.clone(),
+            ))
+            .or_default();
+
+        stats.count += 1;
+        if def.is_harvested() {
+            stats.harvested += 1;
+        }
+        *total_score += u64::from(def.scores.effective);
+    }
+
+    totals
+        .into_iter()
+        .map(|(ecosystem, (mut stats, total_score))| {
+            stats.mean_score = total_score as f32 / stats.count as f32;
+            (ecosystem, stats)
+        })
+        .collect()
+}
+
+/// Counts how many of the definitions were harvested by each tool, eg.
+/// `scancode`, ignoring the version of the tool that was used
+pub fn tools_used(defs: &[Definition]) -> BTreeMap<String, usize> {
+    let mut tools = BTreeMap::new();
+
+    for desc in defs.iter().filter_map(|def| def.described.as_ref()) {
+        let names: BTreeSet<_> = desc
+            .tools
+            .iter()
+            .map(|tool| tool.split_once('/').map_or(tool.as_str(), |(name, _)| name))
+            .collect();
+
+        for name in names {
+            *tools.entry(name.to_owned()).or_default() += 1;
+        }
+    }
+
+    tools
+}
+
+/// The maximum number of coordinates the API accepts in a single request
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// Checks that the coordinates can be sent in a single request, ie. there are
+/// no more than [`MAX_BATCH_SIZE`] of them, for when building requests
+/// manually instead of via [`get`], which automatically chunks them
+pub fn validate_batch(coords: &[crate::Coordinate]) -> Result<(), Error> {
+    if coords.len() > MAX_BATCH_SIZE {
+        return Err(Error::Generic(anyhow::anyhow!(
+            "{} coordinates exceeds the maximum of {} per request, split them into chunks, eg. with `definitions::get`",
+            coords.len(),
+            MAX_BATCH_SIZE
+        )));
+    }
+
+    Ok(())
+}
+
+/// Options for the requests generated by [`get_with`] and [`find_with`]
+#[derive(Clone, Debug)]
+pub struct GetOptions {
+    chunk_size: usize,
+    base_uri: String,
+    user_agent: Option<http::HeaderValue>,
+}
+
+impl Default for GetOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: MAX_BATCH_SIZE,
+            base_uri: crate::ROOT_URI.to_owned(),
+            user_agent: None,
+        }
+    }
+}
+
+impl GetOptions {
+    /// The maximum number of coordinates sent in each request, clamped to
+    /// between 1 and the API limit of [`MAX_BATCH_SIZE`], which is also the
+    /// default
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.clamp(1, MAX_BATCH_SIZE);
+        self
+    }
+
+    /// The root of the API the requests are sent to, defaults to
+    /// [`ROOT_URI`](crate::ROOT_URI)
+    pub fn base_uri(mut self, base_uri: impl Into<String>) -> Self {
+        self.base_uri = base_uri.into();
+        self
+    }
+
+    /// The `User-Agent` header sent with each request, by default no
+    /// `User-Agent` is set
+    pub fn user_agent(mut self, user_agent: http::HeaderValue) -> Self {
+        self.user_agent = Some(user_agent);
+        self
+    }
+}
+
+/// The requirements that definitions must satisfy for [`evaluate`]
+#[cfg(feature = "spdx")]
+#[derive(Clone, Debug, Default)]
+pub struct Policy {
+    /// A license expression of the licenses that are allowed, eg.
+    /// `MIT OR Apache-2.0`, the declared license of each definition must be
+    /// satisfiable using only these licenses. If `None`, any license is allowed.
+    pub allowed_licenses: Option<String>,
+    /// The minimum effective score
+    pub min_score: u8,
+    /// The maximum number of days since the component was released
+    pub max_age_days: Option<u32>,
+}
+
+/// A way in which a definition failed to satisfy a [`Policy`]
+#[cfg(feature = "spdx")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// The component hasn't been harvested, so its license and release date
+    /// are unknown
+    NotHarvested,
+    /// The declared license can't be satisfied by the allowed licenses
+    License(String),
+    /// The effective score is below the minimum
+    Score(u8),
+    /// The component was released this many days ago, more than the maximum
+    Age(u32),
+}
+
+/// The result of evaluating definitions against a [`Policy`]
+#[cfg(feature = "spdx")]
+#[derive(Debug, Default)]
+pub struct Verdict {
+    /// The violations for each coordinate that didn't satisfy the policy,
+    /// coordinates that satisfied it are not present
+    pub violations: BTreeMap<String, Vec<Violation>>,
+}
+
+#[cfg(feature = "spdx")]
+impl Verdict {
+    /// Returns true if every definition satisfied the policy
+    #[inline]
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Evaluates every definition against the policy, eg. to fail a CI job if any
+/// dependencies have licenses that aren't allowed or are poorly scored
+#[cfg(feature = "spdx")]
+pub fn evaluate(defs: &[Definition], policy: &Policy) -> Verdict {
+    let allowed = policy.allowed_licenses.as_ref().map(|allowed| {
+        spdx::Expression::parse_mode(allowed, spdx::ParseMode::LAX).map_or_else(
+            |_| Vec::new(),
+            |expr| expr.requirements().map(|er| er.req.clone()).collect(),
+        )
+    });
+
+    let today = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| (elapsed.as_secs() / 86400) as i64);
+
+    let mut verdict = Verdict::default();
+
+    for def in defs {
+        let mut violations = Vec::new();
+
+        if def.scores.effective < policy.min_score {
+            violations.push(Violation::Score(def.scores.effective));
+        }
+
+        match (&def.described, &def.licensed) {
+            (Some(described), Some(licensed)) => {
+                if let Some(allowed) = &allowed {
+                    let satisfied = licensed.declared_expression().is_some_and(|expr| {
+                        expr.evaluate(|req| {
+                            allowed.iter().any(|allowed: &spdx::LicenseReq| {
+                                allowed.license == req.license && allowed.exception == req.exception
+                            })
+                        })
+                    });
+
+                    if !satisfied {
+                        violations.push(Violation::License(licensed.declared.clone()));
+                    }
+                }
+
+                if let Some(max_age) = policy.max_age_days {
+                    let age = today - days_since_epoch(&described.release_date);
+                    if age > i64::from(max_age) {
+                        violations.push(Violation::Age(age as u32));
+                    }
+                }
+            }
+            _ => {
+                if policy.allowed_licenses.is_some() || policy.max_age_days.is_some() {
+                    violations.push(Violation::NotHarvested);
+                }
+            }
+        }
+
+        if !violations.is_empty() {
+            verdict
+                .violations
+                .insert(def.coordinates.to_string(), violations);
+        }
+    }
+
+    verdict
+}
+
+/// Calculates the number of days between the Unix epoch and the date
+#[cfg(feature = "spdx")]
+fn days_since_epoch(date: &Date) -> i64 {
+    // https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let month = i64::from(date.month);
+    let year = i64::from(date.year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(date.day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
 /// Gets the definitions for the supplied coordinates, note that in addition to
 /// this API call being limited to a maximum of 1000 coordinates per request,
 /// the request time is sometimes _extremely_ slow and can timeout, so it is
 /// recommended you specify a reasonable chunk size and send multiple parallel
 /// requests to reduce wall time.
+///
+/// Coordinates that specify a [`curation_pr`](crate::Coordinate::curation_pr)
+/// are sent with their `/pr/<number>` suffix, so the returned definitions will
+/// have the curations from that PR applied.
+///
+/// This is equivalent to [`get_with`] using the default [`GetOptions`] with
+/// the specified chunk size.
 pub fn get<I>(chunk_size: usize, coordinates: I) -> impl Iterator<Item = Request<Bytes>>
 where
     I: IntoIterator<Item = crate::Coordinate>,
 {
-    let chunk_size = std::cmp::min(chunk_size, 1000);
+    get_with(&GetOptions::default().chunk_size(chunk_size), coordinates)
+}
+
+/// Gets the definitions for the supplied coordinates, the same as [`get`], but
+/// with the requests configured by the specified [`GetOptions`]
+pub fn get_with<I>(options: &GetOptions, coordinates: I) -> impl Iterator<Item = Request<Bytes>>
+where
+    I: IntoIterator<Item = crate::Coordinate>,
+{
+    let chunk_size = options.chunk_size;
     let mut requests = Vec::new();
     let mut coords = Vec::with_capacity(chunk_size);
 
@@ -333,13 +1400,20 @@ where
         requests.push(coords);
     }
 
-    requests.into_iter().map(|req| {
-        let rb = http::Request::builder()
+    let uri = format!("{}/definitions", options.base_uri.trim_end_matches('/'));
+    let user_agent = options.user_agent.clone();
+
+    requests.into_iter().map(move |req| {
+        let mut rb = http::Request::builder()
             .method(http::Method::POST)
-            .uri(format!("{}/definitions", crate::ROOT_URI))
+            .uri(&uri)
             .header(http::header::CONTENT_TYPE, "application/json")
             .header(http::header::ACCEPT, "application/json");
 
+        if let Some(user_agent) = &user_agent {
+            rb = rb.header(http::header::USER_AGENT, user_agent);
+        }
+
         // This..._shouldn't_? fail
         let json = serde_json::to_vec(&serde_json::Value::Array(req))
             .expect("failed to serialize coordinates");
@@ -348,11 +1422,93 @@ where
     })
 }
 
+/// Splits the coordinates into chunked [`get`] requests, and then distributes
+/// those requests as evenly as possible between the specified number of
+/// workers so that they can be executed in parallel. If there are fewer
+/// requests than workers, only one bucket per request is returned.
+pub fn partition<I>(coordinates: I, workers: usize, chunk_size: usize) -> Vec<Vec<Request<Bytes>>>
+where
+    I: IntoIterator<Item = crate::Coordinate>,
+{
+    let requests: Vec<_> = get(chunk_size, coordinates).collect();
+    let workers = std::cmp::min(std::cmp::max(workers, 1), requests.len());
+
+    let mut buckets: Vec<_> = (0..workers).map(|_| Vec::new()).collect();
+    for (i, req) in requests.into_iter().enumerate() {
+        buckets[i % workers].push(req);
+    }
+
+    buckets
+}
+
 pub struct GetResponse {
     /// The component definitions, one for each coordinate passed to the get request
     pub definitions: Vec<Definition>,
 }
 
+impl GetResponse {
+    /// Finds the coordinate in the originally requested set that the definition
+    /// is for. This is needed to recover information that isn't present in
+    /// the definition's coordinates, such as the namespace or curation PR.
+    pub fn coordinate_for<'c>(
+        def: &Definition,
+        original: &'c [crate::Coordinate],
+    ) -> Option<&'c crate::Coordinate> {
+        original
+            .iter()
+            .find(|coord| coord.matches(&def.coordinates))
+    }
+
+    /// The fraction of the definitions in the response that have been
+    /// harvested, or 0 if the response is empty
+    pub fn harvest_rate(&self) -> f32 {
+        if self.definitions.is_empty() {
+            return 0.0;
+        }
+
+        let harvested = self
+            .definitions
+            .iter()
+            .filter(|def| def.is_harvested())
+            .count();
+        harvested as f32 / self.definitions.len() as f32
+    }
+
+    /// Pairs each of the requested coordinates, in order, with the definition
+    /// in the response for it, if there is one, see [`Coordinate::matches`](crate::Coordinate::matches)
+    pub fn correlate<'d>(
+        &'d self,
+        requested: &[crate::Coordinate],
+    ) -> Vec<(crate::Coordinate, Option<&'d Definition>)> {
+        requested
+            .iter()
+            .map(|coord| {
+                let def = self
+                    .definitions
+                    .iter()
+                    .find(|def| coord.matches(&def.coordinates));
+                (coord.clone(), def)
+            })
+            .collect()
+    }
+
+    /// Gets the coordinates from the requested set that either weren't present
+    /// in the response, or which have not been harvested, eg. so that they can
+    /// be queued for harvesting
+    pub fn missing(&self, requested: &[crate::Coordinate]) -> Vec<crate::Coordinate> {
+        requested
+            .iter()
+            .filter(|coord| {
+                !self
+                    .definitions
+                    .iter()
+                    .any(|def| def.is_harvested() && coord.matches(&def.coordinates))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
 impl ApiResponse<&[u8]> for GetResponse {}
 impl ApiResponse<bytes::Bytes> for GetResponse {}
 
@@ -364,6 +1520,19 @@ where
 
     fn try_from(response: http::Response<B>) -> Result<Self, Self::Error> {
         let (_parts, body) = response.into_parts();
+        let body = crate::strip_bom(body.as_ref());
+
+        // The API returns an object keyed by coordinate, but some proxies
+        // instead return a plain array of the definitions
+        let is_array = body
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| *b == b'[');
+
+        if is_array {
+            let definitions: Vec<Definition> = crate::from_body(body)?;
+            return Ok(Self { definitions });
+        }
 
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
@@ -372,7 +1541,7 @@ where
             items: BTreeMap<String, Definition>,
         }
 
-        let res: RawGetResponse = serde_json::from_slice(body.as_ref())?;
+        let res: RawGetResponse = crate::from_body(body)?;
 
         let mut v = Vec::with_capacity(res.items.len());
         for (_, val) in res.items {
@@ -382,3 +1551,186 @@ where
         Ok(Self { definitions: v })
     }
 }
+
+/// Searches for the coordinates of every definition that matches the supplied
+/// pattern, eg. `syn` will return the coordinates for every harvested revision
+/// of the `syn` crate, as well as any other component whose name contains `syn`
+pub fn search(pattern: &str) -> Request<Bytes> {
+    let mut uri = url::Url::parse(crate::ROOT_URI).expect("invalid root uri");
+    uri.set_path("definitions");
+    uri.query_pairs_mut().append_pair("pattern", pattern);
+
+    http::Request::builder()
+        .method(http::Method::GET)
+        .uri(uri.as_str())
+        .header(http::header::ACCEPT, "application/json")
+        .body(Bytes::new())
+        .expect("failed to build request")
+}
+
+pub struct SearchResponse {
+    /// The coordinates that matched the search pattern
+    pub coordinates: Vec<crate::Coordinate>,
+}
+
+impl SearchResponse {
+    /// Gets the sorted revisions of every coordinate in the search results
+    /// whose name matches the specified name
+    pub fn revisions_for(&self, name: &str) -> Vec<crate::CoordVersion> {
+        let mut revisions: Vec<_> = self
+            .coordinates
+            .iter()
+            .filter(|coord| coord.name == name)
+            .map(|coord| coord.version.clone())
+            .collect();
+
+        // Semver versions are sorted before any other kinds of versions
+        revisions.sort();
+
+        revisions
+    }
+}
+
+impl ApiResponse<&[u8]> for SearchResponse {}
+impl ApiResponse<bytes::Bytes> for SearchResponse {}
+
+impl<B> TryFrom<http::Response<B>> for SearchResponse
+where
+    B: AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn try_from(response: http::Response<B>) -> Result<Self, Self::Error> {
+        let (_parts, body) = response.into_parts();
+
+        let res: Vec<&str> = crate::from_body(body.as_ref())?;
+
+        // The search can return coordinates for shapes and providers that we
+        // don't (yet) support, so just skip those rather than failing the
+        // entire response
+        let coordinates = res
+            .into_iter()
+            .filter_map(|coord| coord.parse().ok())
+            .collect();
+
+        Ok(Self { coordinates })
+    }
+}
+
+/// Finds the definitions for every harvested revision of a component, the
+/// results are paginated, so if the [`FindResponse`] has a
+/// [`continuation_token`](FindResponse::continuation_token), it should be
+/// passed to a subsequent request to get the next page of results
+///
+/// This is equivalent to [`find_with`] using the default [`GetOptions`].
+pub fn find(
+    shape: &crate::Shape,
+    provider: &crate::Provider,
+    namespace: Option<&str>,
+    name: &str,
+    continuation_token: Option<&str>,
+) -> Request<Bytes> {
+    find_with(
+        &GetOptions::default(),
+        shape,
+        provider,
+        namespace,
+        name,
+        continuation_token,
+    )
+}
+
+/// Finds the definitions for every harvested revision of a component, the
+/// same as [`find`], but with the request configured by the specified
+/// [`GetOptions`]. The chunk size is not used as the results are paginated
+/// by the API.
+pub fn find_with(
+    options: &GetOptions,
+    shape: &crate::Shape,
+    provider: &crate::Provider,
+    namespace: Option<&str>,
+    name: &str,
+    continuation_token: Option<&str>,
+) -> Request<Bytes> {
+    let mut uri = url::Url::parse(&format!(
+        "{}/definitions",
+        options.base_uri.trim_end_matches('/')
+    ))
+    .expect("invalid base uri");
+
+    {
+        let mut query = uri.query_pairs_mut();
+        query
+            .append_pair("type", shape.as_str())
+            .append_pair("provider", provider.as_str());
+
+        if let Some(namespace) = namespace {
+            query.append_pair("namespace", namespace);
+        }
+
+        query.append_pair("name", name);
+
+        if let Some(token) = continuation_token {
+            query.append_pair("continuationToken", token);
+        }
+    }
+
+    let mut rb = http::Request::builder()
+        .method(http::Method::GET)
+        .uri(uri.as_str())
+        .header(http::header::ACCEPT, "application/json");
+
+    if let Some(user_agent) = &options.user_agent {
+        rb = rb.header(http::header::USER_AGENT, user_agent);
+    }
+
+    rb.body(Bytes::new()).expect("failed to build request")
+}
+
+pub struct FindResponse {
+    /// The coordinates of the definitions in this page of results
+    pub coordinates: Vec<crate::Coordinate>,
+    /// The token used to request the next page of results, if there is one
+    pub continuation_token: Option<String>,
+}
+
+impl ApiResponse<&[u8]> for FindResponse {}
+impl ApiResponse<bytes::Bytes> for FindResponse {}
+
+impl<B> TryFrom<http::Response<B>> for FindResponse
+where
+    B: AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn try_from(response: http::Response<B>) -> Result<Self, Self::Error> {
+        #[derive(Deserialize)]
+        struct Found {
+            coordinates: DefCoords,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RawFindResponse {
+            data: Vec<serde_json::Value>,
+            continuation_token: Option<String>,
+        }
+
+        let (_parts, body) = response.into_parts();
+        let res: RawFindResponse = crate::from_body(body.as_ref())?;
+
+        // Same as search, skip definitions for shapes and providers that we
+        // don't support rather than failing the entire response
+        let coordinates = res
+            .data
+            .iter()
+            .filter_map(|def| Found::deserialize(def).ok())
+            .map(|found| crate::Coordinate::from(found.coordinates))
+            .collect();
+
+        Ok(Self {
+            coordinates,
+            continuation_token: res.continuation_token.filter(|token| !token.is_empty()),
+        })
+    }
+}
@@ -5,16 +5,143 @@ pub enum Error {
     #[error(transparent)]
     Http(#[from] http::Error),
     #[cfg(feature = "client")]
-    #[error(transparent)]
-    Reqwest(#[from] reqwest::Error),
-    #[error("HTTP status: {}", _0)]
-    HttpStatus(#[source] HttpStatusError),
+    #[error("{}{}", source, RequestId(request_id))]
+    Reqwest {
+        #[source]
+        source: reqwest::Error,
+        /// The `X-Request-Id` that was sent with the failed request, if any
+        request_id: Option<String>,
+    },
+    #[error("HTTP status: {}{}", source, RequestId(request_id))]
+    HttpStatus {
+        #[source]
+        source: HttpStatusError,
+        /// The `X-Request-Id` that was sent with the failed request, if any
+        request_id: Option<String>,
+    },
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+    #[cfg(feature = "csv")]
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[cfg(any(feature = "cargo", feature = "cargo-metadata"))]
+    #[error("unable to create a coordinate for '{} {}': {}", _0.name, _0.version, _0.reason)]
+    UnsupportedSource(crate::SkippedPackage),
+    #[error("the coordinate is missing its {}", _0)]
+    MissingField(&'static str),
+    #[error("invalid coordinate '{}': {}", input, source)]
+    ParseCoordinate {
+        /// The string that failed to parse
+        input: String,
+        #[source]
+        source: CoordinateParseError,
+    },
+    #[cfg(feature = "purl")]
+    #[error("invalid purl '{}': {}", input, source)]
+    ParsePurl {
+        /// The string that failed to parse
+        input: String,
+        #[source]
+        source: packageurl::Error,
+    },
     #[error("other error: {}", _0)]
     Generic(#[from] anyhow::Error),
 }
 
+impl Error {
+    /// Returns true if the request that failed with this error may succeed
+    /// if it is sent again, eg. because the service was temporarily
+    /// overloaded, or the request timed out
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::HttpStatus { source, .. } => is_retryable_status(source.0),
+            #[cfg(feature = "client")]
+            Self::Reqwest { source, .. } => {
+                source.is_timeout()
+                    || source.is_connect()
+                    || source.status().is_some_and(is_retryable_status)
+            }
+            _ => false,
+        }
+    }
+
+    /// The `X-Request-Id` that was sent with the request that failed, if the
+    /// client was configured to send one, so that the failure can be
+    /// correlated with the server's logs
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Self::HttpStatus { request_id, .. } => request_id.as_deref(),
+            #[cfg(feature = "client")]
+            Self::Reqwest { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Attaches the id of the request that failed to the error, if it is an
+    /// error that was caused by sending the request
+    #[cfg(feature = "client")]
+    pub(crate) fn with_request_id(mut self, id: Option<String>) -> Self {
+        if let Self::HttpStatus { request_id, .. } | Self::Reqwest { request_id, .. } = &mut self {
+            *request_id = id;
+        }
+
+        self
+    }
+}
+
+/// Appends the request id, if there is one, to the display of an error
+struct RequestId<'a>(&'a Option<String>);
+
+impl fmt::Display for RequestId<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(id) => write!(f, " (request id: {})", id),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns true if a response with the specified status code may succeed if
+/// the request is sent again, namely `429 Too Many Requests`, or one of the
+/// `500`, `502`, `503`, or `504` server errors
+pub fn is_retryable_status(code: http::StatusCode) -> bool {
+    use http::StatusCode;
+
+    matches!(
+        code,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// The reason a string could not be parsed as a [`Coordinate`](crate::Coordinate),
+/// segments are referred to by name, eg. `"namespace"` or `"version"`
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum CoordinateParseError {
+    #[error("missing {}", _0)]
+    MissingSegment(&'static str),
+    #[error("the {} is empty", _0)]
+    EmptySegment(&'static str),
+    #[error("unknown shape '{}'", _0)]
+    UnknownShape(String),
+    #[error("unknown provider '{}'", _0)]
+    UnknownProvider(String),
+    #[error("invalid encoding in the {} '{}'", segment, value)]
+    InvalidEncoding {
+        segment: &'static str,
+        value: String,
+    },
+    #[error("invalid curation PR number '{}'", _0)]
+    InvalidPr(String),
+    #[error("unknown trailing path component '{}'", _0)]
+    TrailingSegment(String),
+    #[error("trailing '/'")]
+    TrailingSlash,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub struct HttpStatusError(pub http::StatusCode);
 
@@ -26,6 +153,19 @@ impl fmt::Display for HttpStatusError {
 
 impl From<http::StatusCode> for Error {
     fn from(e: http::StatusCode) -> Self {
-        Error::HttpStatus(HttpStatusError(e))
+        Error::HttpStatus {
+            source: HttpStatusError(e),
+            request_id: None,
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Reqwest {
+            source: e,
+            request_id: None,
+        }
     }
 }
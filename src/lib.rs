@@ -1,4 +1,6 @@
 #![doc = include_str!("../README.md")]
+// Some of the standard lints below have since been renamed or removed in newer toolchains
+#![allow(renamed_and_removed_lints)]
 // BEGIN - Embark standard lints v5 for Rust 1.55+
 // do not change or add/remove here, but one can add exceptions after this section
 // for more info see: <https://github.com/EmbarkStudios/rust-ecosystem/issues/59>
@@ -78,16 +80,29 @@
 )]
 // END - Embark standard lints v0.5 for Rust 1.55+
 
+#[cfg(any(feature = "cargo", feature = "cargo-metadata"))]
+mod cargo;
 #[cfg(feature = "client")]
 pub mod client;
+#[cfg(feature = "cargo-metadata")]
+mod metadata;
+#[cfg(feature = "purl")]
+mod purl;
+mod set;
 
+pub mod debug;
 pub mod definitions;
 pub mod error;
 
-pub use error::Error;
+#[cfg(any(feature = "cargo", feature = "cargo-metadata"))]
+pub use cargo::{SkippedPackage, UnsupportedSource};
+pub use error::{CoordinateParseError, Error};
+#[cfg(feature = "cargo-metadata")]
+pub use metadata::coordinates_from_metadata;
+pub use set::CoordinateSet;
 
 use serde::Deserialize;
-use std::{convert::TryFrom, fmt, str::FromStr};
+use std::{borrow::Cow, convert::TryFrom, fmt, str::FromStr};
 
 pub use camino::Utf8PathBuf;
 
@@ -97,22 +112,59 @@ pub const ROOT_URI: &str = "https://api.clearlydefined.io";
 // type/provider/namespace/name/revision
 // https://api.clearlydefined.io
 
+/// How unknown shapes and providers are handled when parsing coordinates
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParsePolicy {
+    /// Unknown shapes and providers are an error
+    #[default]
+    Strict,
+    /// Unknown shapes and providers are parsed as [`Shape::Other`] and
+    /// [`Provider::Other`], so that coordinates for components that aren't
+    /// (yet) supported by this crate can still be used
+    Lenient,
+}
+
 /// The "type" of the component
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum Shape {
     /// A Rust Crate
     Crate,
     Git,
-    //Composer,
-    //Pod,
-    //Maven,
-    //Npm,
-    //NuGet,
-    //PyPi,
-    //Gem,
-    //SourceArchive,
-    //Deb,
-    //DebianSources,
+    /// A Javascript package
+    Npm,
+    /// A Java package, whose namespace is the groupId
+    Maven,
+    /// A Python package
+    PyPi,
+    /// A Ruby gem
+    Gem,
+    /// An iOS or macOS package
+    Pod,
+    /// A PHP package, whose namespace is the vendor
+    Composer,
+    /// A Debian binary package, the revision includes the architecture, eg.
+    /// `1:2.36.1-8_amd64`
+    Deb,
+    /// A Debian source package, only valid for the debian provider
+    DebSrc,
+    /// The source archive of a package, eg. the `-sources.jar` of a maven
+    /// package, only valid for providers that publish source archives
+    SourceArchive,
+    /// A Go module, whose namespace is the module path up to the last `/`,
+    /// eg. `github.com/stretchr` for `github.com/stretchr/testify`
+    Go,
+    /// A conda package, whose namespace is the platform subdir, eg. `linux-64`
+    /// or `noarch`, and whose revision is the version followed by the build
+    /// string, eg. `1.26.4-py311h64a7726_0`
+    Conda,
+    /// The source of a conda package, only valid for the conda providers
+    CondaSrc,
+    /// A .NET package
+    NuGet,
+    /// A shape that isn't known by this crate, only produced when parsing
+    /// with [`ParsePolicy::Lenient`], or when deserializing, so that new
+    /// shapes added to the API don't fail the entire response
+    Other(String),
 }
 
 impl<'de> Deserialize<'de> for Shape {
@@ -125,28 +177,107 @@ impl<'de> Deserialize<'de> for Shape {
 }
 
 impl Shape {
+    /// Every shape known by this crate, eg. to list the supported shapes in a
+    /// CLI
+    pub const ALL: &'static [Shape] = &[
+        Shape::Crate,
+        Shape::Git,
+        Shape::Npm,
+        Shape::Maven,
+        Shape::PyPi,
+        Shape::Gem,
+        Shape::Pod,
+        Shape::Composer,
+        Shape::Deb,
+        Shape::DebSrc,
+        Shape::SourceArchive,
+        Shape::Go,
+        Shape::Conda,
+        Shape::CondaSrc,
+        Shape::NuGet,
+    ];
+
     #[inline]
-    pub fn as_str(self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Crate => "crate",
             Self::Git => "git",
+            Self::Npm => "npm",
+            Self::Maven => "maven",
+            Self::PyPi => "pypi",
+            Self::Gem => "gem",
+            Self::Pod => "pod",
+            Self::Composer => "composer",
+            Self::Deb => "deb",
+            Self::DebSrc => "debsrc",
+            Self::SourceArchive => "sourcearchive",
+            Self::Go => "go",
+            Self::Conda => "conda",
+            Self::CondaSrc => "condasrc",
+            Self::NuGet => "nuget",
+            Self::Other(other) => other,
+        }
+    }
+
+    /// Parses a shape, ignoring case, with unknown shapes handled according
+    /// to the policy
+    pub fn from_str_with(s: &str, policy: ParsePolicy) -> Result<Self, Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "crate" => Ok(Shape::Crate),
+            "git" => Ok(Shape::Git),
+            "npm" => Ok(Shape::Npm),
+            "maven" => Ok(Shape::Maven),
+            "pypi" => Ok(Shape::PyPi),
+            "gem" => Ok(Shape::Gem),
+            "pod" => Ok(Shape::Pod),
+            "composer" => Ok(Shape::Composer),
+            "deb" => Ok(Shape::Deb),
+            "debsrc" => Ok(Shape::DebSrc),
+            "sourcearchive" => Ok(Shape::SourceArchive),
+            "go" => Ok(Shape::Go),
+            "conda" => Ok(Shape::Conda),
+            "condasrc" => Ok(Shape::CondaSrc),
+            "nuget" => Ok(Shape::NuGet),
+            _ => match policy {
+                ParsePolicy::Strict => {
+                    Err(Error::Generic(anyhow::anyhow!("unknown shape '{}'", s)))
+                }
+                ParsePolicy::Lenient => Ok(Shape::Other(s.to_owned())),
+            },
         }
     }
 }
 
-impl DeFromStr for Shape {}
+impl DeFromStr for Shape {
+    fn des(s: &str) -> Result<Self, Error> {
+        Self::from_str_with(s, ParsePolicy::Lenient)
+    }
+}
+
+impl serde::Serialize for Shape {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl FromStr for Shape {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "crate" => Ok(Shape::Crate),
-            "git" => Ok(Shape::Git),
-            o => Err(Error::Generic(anyhow::anyhow!("unknown shape '{}'", o))),
-        }
+        Self::from_str_with(s, ParsePolicy::Strict)
     }
 }
 
+/// How a type is parsed when deserialized, which defaults to [`FromStr`]
 trait DeFromStr: FromStr<Err = Error> {
     fn des(s: &str) -> Result<Self, Error> {
         Self::from_str(s)
@@ -159,36 +290,223 @@ where
     D: serde::de::Deserializer<'de>,
     T: DeFromStr,
 {
-    <&'de str>::deserialize(d).and_then(|value| T::des(value).map_err(serde::de::Error::custom))
+    // Not borrowed, as eg. `serde_json::Value` can only deserialize owned strings
+    String::deserialize(d).and_then(|value| T::des(&value).map_err(serde::de::Error::custom))
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum Provider {
     /// The canonical crates.io registry for Rust crates
     CratesIo,
     Github,
+    /// gitlab.com, only valid for the git and sourcearchive shapes. Nested
+    /// groups are separated by `/` in the namespace, eg. `group/subgroup`
+    GitLab,
+    /// The npm registry for Javascript packages, scoped packages such as
+    /// `@types/node` use the scope as the namespace
+    Npmjs,
+    /// The Maven Central repository for Java packages
+    MavenCentral,
+    /// Google's Maven repository, eg. for Android packages
+    MavenGoogle,
+    /// The Gradle plugin portal, only valid for the maven shape
+    GradlePlugin,
+    /// The Python Package Index, see [`Coordinate::pypi`] for how package
+    /// names are normalized
+    PyPi,
+    /// The rubygems.org registry for Ruby gems
+    RubyGems,
+    /// The cocoapods.org trunk repository for iOS and macOS packages
+    CocoaPods,
+    /// The packagist.org repository for PHP packages
+    Packagist,
+    /// The Debian package archive
+    Debian,
+    /// The Go module proxy
+    Golang,
+    /// The conda-forge channel for conda packages
+    CondaForge,
+    /// The main channel of the Anaconda repository
+    AnacondaMain,
+    /// The R channel of the Anaconda repository
+    AnacondaR,
+    /// The nuget.org gallery for .NET packages
+    NuGet,
+    /// A provider that isn't known by this crate, only produced when parsing
+    /// with [`ParsePolicy::Lenient`], or when deserializing, so that new
+    /// providers added to the API don't fail the entire response
+    Other(String),
 }
 
 impl Provider {
+    /// Every provider known by this crate, eg. to list the supported providers
+    /// in a CLI
+    pub const ALL: &'static [Provider] = &[
+        Provider::CratesIo,
+        Provider::Github,
+        Provider::GitLab,
+        Provider::Npmjs,
+        Provider::MavenCentral,
+        Provider::MavenGoogle,
+        Provider::GradlePlugin,
+        Provider::PyPi,
+        Provider::RubyGems,
+        Provider::CocoaPods,
+        Provider::Packagist,
+        Provider::Debian,
+        Provider::Golang,
+        Provider::CondaForge,
+        Provider::AnacondaMain,
+        Provider::AnacondaR,
+        Provider::NuGet,
+    ];
+
     #[inline]
-    pub fn as_str(self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::CratesIo => "cratesio",
             Self::Github => "github",
+            Self::GitLab => "gitlab",
+            Self::Npmjs => "npmjs",
+            Self::MavenCentral => "mavencentral",
+            Self::MavenGoogle => "mavengoogle",
+            Self::GradlePlugin => "gradleplugin",
+            Self::PyPi => "pypi",
+            Self::RubyGems => "rubygems",
+            Self::CocoaPods => "cocoapods",
+            Self::Packagist => "packagist",
+            Self::Debian => "debian",
+            Self::Golang => "golang",
+            Self::CondaForge => "conda-forge",
+            Self::AnacondaMain => "anaconda-main",
+            Self::AnacondaR => "anaconda-r",
+            Self::NuGet => "nuget",
+            Self::Other(other) => other,
+        }
+    }
+
+    /// Parses a provider, ignoring case, with unknown providers handled
+    /// according to the policy. See the [`FromStr`] impl for the aliases that
+    /// are accepted.
+    pub fn from_str_with(s: &str, policy: ParsePolicy) -> Result<Self, Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "cratesio" | "crates.io" | "crates-io" => Ok(Provider::CratesIo),
+            "github" | "github.com" => Ok(Provider::Github),
+            "gitlab" => Ok(Provider::GitLab),
+            "npmjs" | "npm" => Ok(Provider::Npmjs),
+            "mavencentral" => Ok(Provider::MavenCentral),
+            "mavengoogle" => Ok(Provider::MavenGoogle),
+            "gradleplugin" => Ok(Provider::GradlePlugin),
+            "pypi" => Ok(Provider::PyPi),
+            "rubygems" => Ok(Provider::RubyGems),
+            "cocoapods" => Ok(Provider::CocoaPods),
+            "packagist" => Ok(Provider::Packagist),
+            "debian" => Ok(Provider::Debian),
+            "golang" => Ok(Provider::Golang),
+            "conda-forge" => Ok(Provider::CondaForge),
+            "anaconda-main" => Ok(Provider::AnacondaMain),
+            "anaconda-r" => Ok(Provider::AnacondaR),
+            "nuget" => Ok(Provider::NuGet),
+            _ => match policy {
+                ParsePolicy::Strict => {
+                    Err(Error::Generic(anyhow::anyhow!("unknown provider '{}'", s)))
+                }
+                ParsePolicy::Lenient => Ok(Provider::Other(s.to_owned())),
+            },
+        }
+    }
+}
+
+impl Provider {
+    /// Returns true if components from the provider are organized into
+    /// namespaces, eg. the owner of a GitHub repository. Note that the
+    /// namespace may still be optional, eg. npm packages that aren't scoped.
+    /// Unknown providers are assumed to have namespaces.
+    #[inline]
+    pub fn has_namespaces(&self) -> bool {
+        match self {
+            Self::CratesIo
+            | Self::PyPi
+            | Self::RubyGems
+            | Self::CocoaPods
+            | Self::Debian
+            | Self::NuGet => false,
+            Self::Github
+            | Self::GitLab
+            | Self::Npmjs
+            | Self::MavenCentral
+            | Self::MavenGoogle
+            | Self::GradlePlugin
+            | Self::Packagist
+            | Self::Golang
+            | Self::CondaForge
+            | Self::AnacondaMain
+            | Self::AnacondaR
+            | Self::Other(_) => true,
+        }
+    }
+
+    /// Returns a human readable name for the provider suitable for display in
+    /// eg. a UI, as opposed to [`Provider::as_str`] which is the name used by
+    /// the API. Unknown providers just use the name they were parsed from.
+    #[inline]
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::CratesIo => "crates.io",
+            Self::Github => "GitHub",
+            Self::GitLab => "GitLab",
+            Self::Npmjs => "npm Registry",
+            Self::MavenCentral => "Maven Central",
+            Self::MavenGoogle => "Google Maven",
+            Self::GradlePlugin => "Gradle Plugin Portal",
+            Self::PyPi => "PyPI",
+            Self::RubyGems => "RubyGems",
+            Self::CocoaPods => "CocoaPods",
+            Self::Packagist => "Packagist",
+            Self::Debian => "Debian",
+            Self::Golang => "Go Module Proxy",
+            Self::CondaForge => "conda-forge",
+            Self::AnacondaMain => "Anaconda (main)",
+            Self::AnacondaR => "Anaconda (R)",
+            Self::NuGet => "NuGet Gallery",
+            Self::Other(other) => other,
         }
     }
 }
 
-impl DeFromStr for Provider {}
+impl DeFromStr for Provider {
+    fn des(s: &str) -> Result<Self, Error> {
+        Self::from_str_with(s, ParsePolicy::Lenient)
+    }
+}
+
+impl serde::Serialize for Provider {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Providers are parsed ignoring case, and in addition to the canonical names
+/// returned by [`Provider::as_str`], the following common aliases are also
+/// accepted
+///
+/// * [`Provider::CratesIo`] - `crates.io`, `crates-io`
+/// * [`Provider::Github`] - `github.com`
+/// * [`Provider::Npmjs`] - `npm`
 impl FromStr for Provider {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "cratesio" => Ok(Provider::CratesIo),
-            "github" => Ok(Provider::Github),
-            o => Err(Error::Generic(anyhow::anyhow!("unknown provider '{}'", o))),
-        }
+        Self::from_str_with(s, ParsePolicy::Strict)
     }
 }
 
@@ -201,10 +519,19 @@ impl<'de> serde::Deserialize<'de> for Provider {
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// The revision of a component
+///
+/// Semver versions are ordered by semver precedence, eg. `1.2.0` is before
+/// `1.10.0`, and `1.0.0-alpha` is before `1.0.0`, other revisions are ordered
+/// lexicographically, and every semver version is ordered before every other
+/// revision. Git commit shas are ordered after all other revisions.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum CoordVersion {
     Semver(semver::Version),
     Any(String),
+    /// A git commit sha, this is only produced when parsing the revision of a
+    /// coordinate with the git shape, see [`CoordVersion::for_shape`]
+    GitSha([u8; 20]),
 }
 
 impl DeFromStr for CoordVersion {}
@@ -221,6 +548,145 @@ impl FromStr for CoordVersion {
     }
 }
 
+impl CoordVersion {
+    /// Parses the revision of a coordinate with the specified shape, for git
+    /// coordinates this is a [`GitSha`](Self::GitSha) if the revision is a
+    /// full, 40 character, commit sha, the same as the [`FromStr`] impl
+    /// otherwise
+    pub fn for_shape(shape: &Shape, s: &str) -> Self {
+        if *shape == Shape::Git {
+            if let Some(sha) = parse_sha(s) {
+                return Self::GitSha(sha);
+            }
+        }
+
+        s.parse().expect("parsing a version is infallible")
+    }
+
+    /// Gets the commit sha, if this is a [`GitSha`](Self::GitSha)
+    #[inline]
+    pub fn as_sha(&self) -> Option<&[u8; 20]> {
+        match self {
+            Self::GitSha(sha) => Some(sha),
+            Self::Semver(_) | Self::Any(_) => None,
+        }
+    }
+
+    /// Gets a short form of the version suitable for display, which is the
+    /// first 7 characters of commit shas, the same as git, and the full
+    /// version for everything else
+    pub fn short(&self) -> String {
+        let mut short = self.to_string();
+        if self.as_sha().is_some() {
+            short.truncate(7);
+        }
+        short
+    }
+
+    /// Parses a version, additionally stripping a `v` or `V` prefix from
+    /// versions that are otherwise valid semver, eg. the `v1.2.3` tags used by
+    /// Go modules and many git repositories are parsed as `1.2.3`. The
+    /// [`FromStr`] impl keeps the prefix, and so parses such versions as
+    /// [`Any`](Self::Any).
+    pub fn from_str_lenient(s: &str) -> Self {
+        match s
+            .strip_prefix(['v', 'V'])
+            .and_then(|vs| vs.parse::<semver::Version>().ok())
+        {
+            Some(vs) => Self::Semver(vs),
+            None => s.parse().expect("parsing a version is infallible"),
+        }
+    }
+
+    /// Checks if the versions refer to the same revision, for correlating the
+    /// versions of eg. requested coordinates with those in a response. Unlike
+    /// `==`, versions are parsed [leniently](Self::from_str_lenient), so that
+    /// `v1.8.0` matches `1.8.0`, and semver versions that only differ in
+    /// their build metadata, eg. `1.0.0+abc` and `1.0.0`, also match.
+    pub fn matches(&self, other: &Self) -> bool {
+        let semver = |vers: &Self| match vers {
+            Self::Semver(vs) => Some(vs.clone()),
+            Self::Any(rev) => match Self::from_str_lenient(rev) {
+                Self::Semver(vs) => Some(vs),
+                Self::Any(_) | Self::GitSha(_) => None,
+            },
+            Self::GitSha(_) => None,
+        };
+
+        match (semver(self), semver(other)) {
+            (Some(a), Some(b)) => {
+                a.major == b.major && a.minor == b.minor && a.patch == b.patch && a.pre == b.pre
+            }
+            _ => match (self, other) {
+                // Revisions that weren't parsed for a git coordinate, eg. in a
+                // response, may still be commit shas
+                (Self::GitSha(sha), Self::Any(rev)) | (Self::Any(rev), Self::GitSha(sha)) => {
+                    parse_sha(rev).as_ref() == Some(sha)
+                }
+                _ => self == other,
+            },
+        }
+    }
+
+    /// Attempts to find a date in an [`Any`](Self::Any) revision, for
+    /// providers that encode dates in their revisions, eg. Debian snapshots.
+    ///
+    /// Dates are detected in either the `YYYY-MM-DD` format, with `-`, `.`, or
+    /// `/` as the separator, or the compact `YYYYMMDD` format, which may be
+    /// followed by a time, eg. `20210314T101500Z`. Returns `None` if no valid
+    /// date can be found.
+    pub fn as_date(&self) -> Option<definitions::Date> {
+        let Self::Any(rev) = self else {
+            return None;
+        };
+
+        let date = |year: &str, month: &str, day: &str| {
+            let date = definitions::Date {
+                year: year.parse().ok()?,
+                month: month.parse().ok()?,
+                day: day.parse().ok()?,
+            };
+
+            ((1900..=9999).contains(&date.year)
+                && (1..=12).contains(&date.month)
+                && (1..=days_in_month(date.year, date.month)).contains(&date.day))
+            .then_some(date)
+        };
+
+        // Split the revision into runs of digits, along with the character
+        // that separates each run from the next one
+        let mut runs = Vec::new();
+        let mut start = None;
+        for (i, c) in rev.char_indices().chain(std::iter::once((rev.len(), ' '))) {
+            match (c.is_ascii_digit(), start) {
+                (true, None) => start = Some(i),
+                (false, Some(s)) => {
+                    runs.push((&rev[s..i], c));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        runs.iter().enumerate().find_map(|(i, (run, sep))| {
+            if run.len() == 4 && matches!(sep, '-' | '.' | '/') {
+                let (month, msep) = runs.get(i + 1)?;
+                let (day, _) = runs.get(i + 2)?;
+
+                if msep == sep && month.len() == 2 && day.len() == 2 {
+                    return date(run, month, day);
+                }
+            }
+
+            if run.len() == 8 || run.len() == 14 {
+                return date(&run[..4], &run[4..6], &run[6..8]);
+            }
+
+            None
+        })
+    }
+}
+
 impl<'de> serde::Deserialize<'de> for CoordVersion {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -230,11 +696,33 @@ impl<'de> serde::Deserialize<'de> for CoordVersion {
     }
 }
 
+impl From<semver::Version> for CoordVersion {
+    fn from(vs: semver::Version) -> Self {
+        Self::Semver(vs)
+    }
+}
+
+impl From<&str> for CoordVersion {
+    fn from(s: &str) -> Self {
+        s.parse().expect("parsing a version is infallible")
+    }
+}
+
+impl serde::Serialize for CoordVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 impl fmt::Display for CoordVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Semver(vs) => write!(f, "{}", vs),
             Self::Any(s) => f.write_str(s),
+            Self::GitSha(sha) => sha.iter().try_for_each(|b| write!(f, "{:02x}", b)),
         }
     }
 }
@@ -242,6 +730,10 @@ impl fmt::Display for CoordVersion {
 /// Defines the coordinates of a specific component
 ///
 /// For example, `crate/cratesio/-/syn/1.0.14`
+///
+/// Coordinates are ordered by their shape, provider, namespace, name, and
+/// then version, see [`CoordVersion`]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Coordinate {
     /// The shape/kind of the component
     pub shape: Shape,
@@ -258,61 +750,947 @@ pub struct Coordinate {
     pub curation_pr: Option<u32>,
 }
 
+impl Coordinate {
+    /// Creates a builder for a coordinate, which validates the coordinate
+    /// once it is built, eg.
+    /// `Coordinate::builder(Shape::Crate, Provider::CratesIo).name("serde").version("1.0.190").build()`
+    pub fn builder(shape: Shape, provider: Provider) -> CoordinateBuilder {
+        CoordinateBuilder {
+            shape,
+            provider,
+            namespace: None,
+            name: None,
+            version: None,
+            curation_pr: None,
+        }
+    }
+
+    /// Creates a coordinate for the `latest` pseudo-revision of a component,
+    /// which Clearly Defined resolves to the latest version known to the
+    /// provider, eg. `crate/cratesio/-/syn/latest`.
+    ///
+    /// This is only meaningful for package registry providers such as
+    /// `cratesio`, which have a notion of the latest published version. Git
+    /// providers have no such concept, so their revisions must be a commit.
+    pub fn latest(shape: Shape, provider: Provider, namespace: Option<&str>, name: &str) -> Self {
+        Self {
+            shape,
+            provider,
+            namespace: namespace.map(String::from),
+            name: name.to_owned(),
+            version: CoordVersion::Any("latest".to_owned()),
+            curation_pr: None,
+        }
+    }
+
+    /// Creates a coordinate for a Python package, normalizing the name the
+    /// same way as the package index per [PEP 503](https://peps.python.org/pep-0503/#normalized-names),
+    /// ie. lowercased with runs of `-`, `_`, and `.` replaced with a single
+    /// `-`, eg. `zope.interface` becomes `zope-interface`, as the definitions
+    /// are stored using the normalized name.
+    pub fn pypi(name: &str, version: &str) -> Self {
+        Self {
+            shape: Shape::PyPi,
+            provider: Provider::PyPi,
+            namespace: None,
+            name: pep503_normalize(name),
+            version: version.parse().expect("parsing a version is infallible"),
+            curation_pr: None,
+        }
+    }
+
+    /// Creates a coordinate for a crate published to crates.io
+    ///
+    /// ```
+    /// let serde = cd::Coordinate::crates_io("serde", &semver::Version::new(1, 0, 190));
+    /// assert_eq!("crate/cratesio/-/serde/1.0.190", serde.to_string());
+    /// ```
+    pub fn crates_io(name: impl Into<String>, version: &semver::Version) -> Self {
+        Self {
+            shape: Shape::Crate,
+            provider: Provider::CratesIo,
+            namespace: None,
+            name: name.into(),
+            version: CoordVersion::Semver(version.clone()),
+            curation_pr: None,
+        }
+    }
+
+    /// Creates a coordinate for a GitHub repository at a specific commit,
+    /// failing if the sha isn't a full 40 character commit sha
+    ///
+    /// ```
+    /// let syn = cd::Coordinate::github(
+    ///     "dtolnay",
+    ///     "syn",
+    ///     "855f331cf0e14916a1c3026786b59e6f6b6f2d6f",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(
+    ///     "git/github/dtolnay/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f",
+    ///     syn.to_string()
+    /// );
+    ///
+    /// assert!(cd::Coordinate::github("dtolnay", "syn", "855f331").is_err());
+    /// ```
+    pub fn github(
+        owner: impl Into<String>,
+        repo: impl Into<String>,
+        sha: &str,
+    ) -> Result<Self, Error> {
+        Self::builder(Shape::Git, Provider::Github)
+            .namespace(owner)
+            .name(repo)
+            .version(sha)
+            .build()
+    }
+
+    /// Checks if the coordinates of a definition, eg. from a
+    /// [`GetResponse`](definitions::GetResponse), refer to the same component
+    /// revision as this coordinate, using the comparison rules of the provider
+    ///
+    /// * Names and namespaces are compared ignoring case for providers whose
+    ///   names are case-insensitive, eg. crates.io, npm, and nuget, and python
+    ///   package names are compared after being normalized the same as
+    ///   [`Coordinate::pypi`]
+    /// * Versions are compared with [`CoordVersion::matches`], so eg. build
+    ///   metadata and a `v` prefix are ignored, and git commit shas are
+    ///   compared ignoring case
+    ///
+    /// The curation PR is ignored, as it is not part of the definition's
+    /// coordinates.
+    pub fn matches(&self, def: &definitions::DefCoords) -> bool {
+        let same_name = |a: &str, b: &str| match self.provider {
+            Provider::PyPi => pep503_normalize(a) == pep503_normalize(b),
+            Provider::CratesIo
+            | Provider::Npmjs
+            | Provider::Packagist
+            | Provider::Debian
+            | Provider::CondaForge
+            | Provider::AnacondaMain
+            | Provider::AnacondaR
+            | Provider::NuGet
+            | Provider::Github => a.eq_ignore_ascii_case(b),
+            Provider::GitLab
+            | Provider::MavenCentral
+            | Provider::MavenGoogle
+            | Provider::GradlePlugin
+            | Provider::RubyGems
+            | Provider::CocoaPods
+            | Provider::Golang
+            | Provider::Other(_) => a == b,
+        };
+
+        self.shape == def.shape
+            && self.provider == def.provider
+            && match (&self.namespace, &def.namespace) {
+                (Some(a), Some(b)) => same_name(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+            && same_name(&self.name, &def.name)
+            && self.version.matches(&def.revision)
+    }
+
+    /// Checks if the other coordinate refers to the same component, ie. the
+    /// shape, provider, namespace, and name are the same, ignoring the version
+    /// and curation PR
+    pub fn is_same_component(&self, other: &Self) -> bool {
+        self.shape == other.shape
+            && self.provider == other.provider
+            && self.namespace == other.namespace
+            && self.name == other.name
+    }
+
+    /// Checks if the version is valid for the rules of the provider's registry,
+    /// eg. crates.io requires all versions to be valid semver, and the Python
+    /// Package Index requires [PEP 440](https://peps.python.org/pep-0440/)
+    /// versions. This can be used to catch typos before sending requests that
+    /// would otherwise just return definitions that haven't been harvested.
+    ///
+    /// The `latest` pseudo-revision, see [`Coordinate::latest`], is valid for
+    /// every provider, and providers without a well defined versioning scheme
+    /// always return true.
+    pub fn version_valid_for_provider(&self) -> bool {
+        if matches!(&self.version, CoordVersion::Any(rev) if rev == "latest") {
+            return true;
+        }
+
+        match self.provider {
+            Provider::CratesIo | Provider::Npmjs => {
+                matches!(self.version, CoordVersion::Semver(_))
+            }
+            Provider::PyPi => is_pep440(&self.version.to_string()),
+            Provider::Github
+            | Provider::GitLab
+            | Provider::MavenCentral
+            | Provider::MavenGoogle
+            | Provider::GradlePlugin
+            | Provider::RubyGems
+            | Provider::CocoaPods
+            | Provider::Packagist
+            | Provider::Debian
+            | Provider::Golang
+            | Provider::CondaForge
+            | Provider::AnacondaMain
+            | Provider::AnacondaR
+            | Provider::NuGet
+            | Provider::Other(_) => true,
+        }
+    }
+
+    /// Gets a representation of the coordinate that is safe to log. Components
+    /// from public package registries are left intact, but the namespace and
+    /// name of git components, which may refer to private repositories, are
+    /// replaced with a short hash so that they can still be correlated with
+    /// each other without revealing the repository. Components with unknown
+    /// shapes are redacted the same way, to be safe.
+    pub fn redacted(&self) -> String {
+        match self.shape {
+            Shape::Git | Shape::Other(_) => {
+                let redact = |s: &str| format!("{:08x}", fnv1a(s.as_bytes()) as u32);
+
+                format!(
+                    "{}/{}/{}/{}/{}",
+                    self.shape.as_str(),
+                    self.provider.as_str(),
+                    self.namespace
+                        .as_deref()
+                        .map_or_else(|| "-".to_owned(), redact),
+                    redact(&self.name),
+                    self.version,
+                )
+            }
+            Shape::Crate
+            | Shape::Npm
+            | Shape::Maven
+            | Shape::PyPi
+            | Shape::Gem
+            | Shape::Pod
+            | Shape::Composer
+            | Shape::Deb
+            | Shape::DebSrc
+            | Shape::SourceArchive
+            | Shape::Go
+            | Shape::Conda
+            | Shape::CondaSrc
+            | Shape::NuGet => self.to_string(),
+        }
+    }
+
+    /// Gets the URL of the API endpoint that returns the JSON definition for
+    /// the coordinate, eg. for linking to the raw data in an issue. This
+    /// includes the curation PR, if there is one.
+    pub fn api_url(&self) -> String {
+        format!("{}/definitions/{}", ROOT_URI, self)
+    }
+
+    /// Parses a coordinate from a link to its definition on either the
+    /// clearlydefined.io website or the API, eg.
+    /// `https://clearlydefined.io/definitions/crate/cratesio/-/syn/1.0.14`, the
+    /// inverse of [`Coordinate::clearlydefined_url`] and [`Coordinate::api_url`]
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        use anyhow::Context as _;
+
+        let parsed = url::Url::parse(url).with_context(|| format!("invalid url '{}'", url))?;
+
+        if !matches!(parsed.scheme(), "http" | "https")
+            || !matches!(
+                parsed.host_str(),
+                Some("clearlydefined.io" | "www.clearlydefined.io" | "api.clearlydefined.io")
+            )
+        {
+            return Err(Error::Generic(anyhow::anyhow!(
+                "'{}' is not a clearlydefined.io url",
+                url
+            )));
+        }
+
+        let mut segments = parsed
+            .path_segments()
+            .with_context(|| format!("'{}' is not a definition url", url))?;
+        if segments.next() != Some("definitions") {
+            return Err(Error::Generic(anyhow::anyhow!(
+                "'{}' is not a definition url",
+                url
+            )));
+        }
+
+        // The segments are still percent-encoded, which is decoded by the
+        // coordinate parsing
+        segments.collect::<Vec<_>>().join("/").parse()
+    }
+
+    /// Gets the URL of the page for the coordinate on the clearlydefined.io
+    /// website, eg. for linking to it in a compliance report
+    pub fn clearlydefined_url(&self) -> String {
+        format!("https://clearlydefined.io/definitions/{}", self)
+    }
+
+    /// Gets the URL of the page for the component in the provider's registry
+    /// or repository, eg. `https://crates.io/crates/syn/1.0.14`.
+    ///
+    /// Returns `None` for unknown providers, or for coordinates the provider
+    /// doesn't have a page for, eg. Debian binary packages.
+    pub fn provider_url(&self) -> Option<String> {
+        let ns = self.namespace.as_deref();
+        let name = &self.name;
+        let vers = &self.version;
+
+        let url = match self.provider {
+            Provider::CratesIo => format!("https://crates.io/crates/{name}/{vers}"),
+            Provider::Github => format!("https://github.com/{}/{name}/tree/{vers}", ns?),
+            Provider::GitLab => format!("https://gitlab.com/{}/{name}/-/tree/{vers}", ns?),
+            Provider::Npmjs => match ns {
+                Some(scope) => format!("https://www.npmjs.com/package/{scope}/{name}/v/{vers}"),
+                None => format!("https://www.npmjs.com/package/{name}/v/{vers}"),
+            },
+            Provider::MavenCentral => format!(
+                "https://central.sonatype.com/artifact/{}/{name}/{vers}",
+                ns?
+            ),
+            Provider::MavenGoogle => {
+                format!(
+                    "https://maven.google.com/web/index.html#{}:{name}:{vers}",
+                    ns?
+                )
+            }
+            // The namespace of gradle plugins is the plugin id
+            Provider::GradlePlugin => format!("https://plugins.gradle.org/plugin/{}/{vers}", ns?),
+            Provider::PyPi => format!("https://pypi.org/project/{name}/{vers}/"),
+            Provider::RubyGems => format!("https://rubygems.org/gems/{name}/versions/{vers}"),
+            Provider::CocoaPods => format!("https://cocoapods.org/pods/{name}"),
+            Provider::Packagist => format!("https://packagist.org/packages/{}/{name}", ns?),
+            Provider::Debian => match self.shape {
+                Shape::DebSrc => format!("https://sources.debian.org/src/{name}/{vers}/"),
+                _ => return None,
+            },
+            Provider::Golang => match ns {
+                Some(ns) => format!("https://pkg.go.dev/{ns}/{name}@{vers}"),
+                None => format!("https://pkg.go.dev/{name}@{vers}"),
+            },
+            Provider::CondaForge => format!("https://anaconda.org/conda-forge/{name}"),
+            Provider::AnacondaMain => format!("https://anaconda.org/anaconda/{name}"),
+            Provider::AnacondaR => format!("https://anaconda.org/r/{name}"),
+            Provider::NuGet => format!("https://www.nuget.org/packages/{name}/{vers}"),
+            Provider::Other(_) => return None,
+        };
+
+        Some(url)
+    }
+
+    /// Gets a short, 8 character, hex hash of the canonical form of the
+    /// coordinate, eg. for use in short URLs or to correlate log entries. The
+    /// hash is stable across runs and platforms, but as it is so short,
+    /// collisions are possible so it shouldn't be used as a unique key.
+    pub fn short_hash(&self) -> String {
+        format!("{:08x}", fnv1a(self.to_string().as_bytes()) as u32)
+    }
+
+    /// Calculates how similar the name of the component is to another name,
+    /// from `0.0` for completely different names to `1.0` for identical ones,
+    /// eg. to flag dependencies that are likely typosquats of popular crates.
+    ///
+    /// The similarity is the [optimal string alignment](https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance#Optimal_string_alignment_distance)
+    /// distance, ie. the Levenshtein distance where a transposition of two
+    /// adjacent characters is also counted as a single edit, divided by the
+    /// length of the longer name and subtracted from 1. The names are compared
+    /// case-insensitively.
+    pub fn name_similarity(&self, other: &str) -> f32 {
+        let a: Vec<char> = self.name.to_lowercase().chars().collect();
+        let b: Vec<char> = other.to_lowercase().chars().collect();
+
+        let longest = std::cmp::max(a.len(), b.len());
+        if longest == 0 {
+            return 1.0;
+        }
+
+        // The distances for the previous two rows and the current row
+        let mut prev2 = vec![0; b.len() + 1];
+        let mut prev: Vec<usize> = (0..=b.len()).collect();
+        let mut cur = vec![0; b.len() + 1];
+
+        for i in 1..=a.len() {
+            cur[0] = i;
+
+            for j in 1..=b.len() {
+                let cost = usize::from(a[i - 1] != b[j - 1]);
+
+                cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    cur[j] = cur[j].min(prev2[j - 2] + 1);
+                }
+            }
+
+            std::mem::swap(&mut prev2, &mut prev);
+            std::mem::swap(&mut prev, &mut cur);
+        }
+
+        1.0 - prev[b.len()] as f32 / longest as f32
+    }
+}
+
+impl Coordinate {
+    /// Checks that the coordinate is one that the API actually supports, as
+    /// otherwise the API will happily accept the coordinate and just return an
+    /// unharvested definition for it. This is called when parsing coordinates,
+    /// but can also be used to check coordinates constructed manually.
+    ///
+    /// * The shape must be supported by the provider, eg. `crate/github` is
+    ///   not valid. Unknown shapes and providers are not checked.
+    /// * The namespace must be present for shapes that require one, eg. the
+    ///   groupId of a maven package, and absent for providers that don't have
+    ///   namespaces, eg. crates.io
+    /// * Git revisions must be a full commit sha
+    ///
+    /// The error describes which of these rules was violated.
+    pub fn validate(&self) -> Result<(), Error> {
+        let supported = match self.shape {
+            Shape::Crate => matches!(self.provider, Provider::CratesIo),
+            Shape::Git => matches!(self.provider, Provider::Github | Provider::GitLab),
+            Shape::Npm => matches!(self.provider, Provider::Npmjs),
+            Shape::Maven => matches!(
+                self.provider,
+                Provider::MavenCentral | Provider::MavenGoogle | Provider::GradlePlugin
+            ),
+            Shape::PyPi => matches!(self.provider, Provider::PyPi),
+            Shape::Gem => matches!(self.provider, Provider::RubyGems),
+            Shape::Pod => matches!(self.provider, Provider::CocoaPods),
+            Shape::Composer => matches!(self.provider, Provider::Packagist),
+            Shape::Deb | Shape::DebSrc => matches!(self.provider, Provider::Debian),
+            Shape::SourceArchive => matches!(
+                self.provider,
+                Provider::MavenCentral | Provider::MavenGoogle | Provider::Npmjs | Provider::GitLab
+            ),
+            Shape::Go => matches!(self.provider, Provider::Golang),
+            Shape::Conda | Shape::CondaSrc => matches!(
+                self.provider,
+                Provider::CondaForge | Provider::AnacondaMain | Provider::AnacondaR
+            ),
+            Shape::NuGet => matches!(self.provider, Provider::NuGet),
+            Shape::Other(_) => true,
+        };
+
+        if !supported && !matches!(self.provider, Provider::Other(_)) {
+            return Err(Error::Generic(anyhow::anyhow!(
+                "the '{}' shape is not supported by the '{}' provider",
+                self.shape,
+                self.provider
+            )));
+        }
+
+        let required_namespace = match self.shape {
+            Shape::Maven => Some("the groupId"),
+            Shape::Composer => Some("the vendor"),
+            Shape::Git => Some("the owner of the repository"),
+            _ => None,
+        };
+
+        match (&self.namespace, required_namespace) {
+            (None, Some(what)) => {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "{} coordinates require a namespace ({})",
+                    self.shape,
+                    what
+                )));
+            }
+            (Some(ns), _) if !self.provider.has_namespaces() => {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "the '{}' provider does not have namespaces, but '{}' was specified",
+                    self.provider,
+                    ns
+                )));
+            }
+            _ => {}
+        }
+
+        if self.shape == Shape::Git && self.version.as_sha().is_none() {
+            return Err(Error::Generic(anyhow::anyhow!(
+                "git revisions must be a full 40 character commit sha, not '{}'",
+                self.version
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a [`Coordinate`], see [`Coordinate::builder`]
+#[derive(Clone, Debug)]
+pub struct CoordinateBuilder {
+    shape: Shape,
+    provider: Provider,
+    namespace: Option<String>,
+    name: Option<String>,
+    version: Option<CoordVersion>,
+    curation_pr: Option<u32>,
+}
+
+impl CoordinateBuilder {
+    /// The namespace of the component, this is required by some shapes, eg.
+    /// the groupId of a maven package, and not allowed for providers that
+    /// don't have namespaces, eg. crates.io
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// The name of the component, this is required
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// The version of the component, this is required, and can be either a
+    /// [`semver::Version`], a string, or a [`CoordVersion`]
+    pub fn version(mut self, version: impl Into<CoordVersion>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// A curation PR to apply to the definition of the component
+    pub fn curation_pr(mut self, pr: u32) -> Self {
+        self.curation_pr = Some(pr);
+        self
+    }
+
+    /// Builds the coordinate, failing with [`Error::MissingField`] if the name
+    /// or version weren't set, or if the coordinate isn't
+    /// [valid](Coordinate::validate), or the version isn't
+    /// [valid for the provider](Coordinate::version_valid_for_provider)
+    pub fn build(self) -> Result<Coordinate, Error> {
+        let version = match self.version.ok_or(Error::MissingField("version"))? {
+            CoordVersion::Any(rev) => CoordVersion::for_shape(&self.shape, &rev),
+            version => version,
+        };
+
+        let coord = Coordinate {
+            shape: self.shape,
+            provider: self.provider,
+            namespace: self.namespace,
+            name: self.name.ok_or(Error::MissingField("name"))?,
+            version,
+            curation_pr: self.curation_pr,
+        };
+
+        coord.validate()?;
+
+        if !coord.version_valid_for_provider() {
+            return Err(Error::Generic(anyhow::anyhow!(
+                "version '{}' is not valid for the '{}' provider",
+                coord.version,
+                coord.provider
+            )));
+        }
+
+        Ok(coord)
+    }
+}
+
+/// Percent-encodes the characters in a segment of a coordinate that would
+/// otherwise change the structure of the coordinate, or of a URI it is used
+/// in, eg. the `/` separators of a nested gitlab group. Other characters, such
+/// as the `@` of npm scopes and the `+` in Debian revisions, are kept as they
+/// are, matching the coordinates returned by the API.
+fn encode_segment(segment: &str) -> Cow<'_, str> {
+    let needs_encoding =
+        |b: u8| b.is_ascii_control() || matches!(b, b' ' | b'"' | b'#' | b'%' | b'/' | b'?');
+
+    if !segment.bytes().any(needs_encoding) {
+        return Cow::Borrowed(segment);
+    }
+
+    let mut encoded = String::with_capacity(segment.len() + 8);
+    for c in segment.chars() {
+        if c.is_ascii() && needs_encoding(c as u8) {
+            // The API uses lowercase hex digits, eg. `%2f`
+            encoded.push_str(&format!("%{:02x}", c as u8));
+        } else {
+            encoded.push(c);
+        }
+    }
+
+    Cow::Owned(encoded)
+}
+
+/// Decodes every percent-encoded character in a segment of a coordinate, the
+/// inverse of [`encode_segment`]
+fn decode_segment(segment: &str) -> Result<Cow<'_, str>, std::str::Utf8Error> {
+    percent_encoding::percent_decode_str(segment).decode_utf8()
+}
+
+/// Decodes the namespace or name of a component, which may be percent-encoded,
+/// see [`encode_segment`], and Go module paths have an additional escaping
+/// scheme, see [`go_escape`]
+pub(crate) fn decode_component(shape: &Shape, segment: &str) -> Option<String> {
+    let decoded = decode_segment(segment).ok()?;
+    if *shape == Shape::Go {
+        go_unescape(&decoded)
+    } else {
+        Some(decoded.into_owned())
+    }
+}
+
+/// Escapes part of a Go module path the same way as the Go module proxy, with
+/// uppercase letters replaced by a `!` followed by the lowercase letter, eg.
+/// `BurntSushi` becomes `!burnt!sushi`, and then encodes it the same as every
+/// other segment with [`encode_segment`]
+fn go_escape(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            c if c.is_ascii_uppercase() => {
+                escaped.push('!');
+                escaped.push(c.to_ascii_lowercase());
+            }
+            c => escaped.push(c),
+        }
+    }
+    encode_segment(&escaped).into_owned()
+}
+
+/// Reverses [`go_escape`] for a segment that has already been decoded with
+/// [`decode_segment`]
+fn go_unescape(path: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        if c != '!' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(c) if c.is_ascii_lowercase() => unescaped.push(c.to_ascii_uppercase()),
+            _ => return None,
+        }
+    }
+
+    Some(unescaped)
+}
+
+/// Normalizes a Python package name per [PEP 503](https://peps.python.org/pep-0503/#normalized-names)
+fn pep503_normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.extend(c.to_lowercase());
+        }
+    }
+    normalized
+}
+
+/// Loosely checks that the version is a [PEP 440](https://peps.python.org/pep-0440/)
+/// version, which is required by the Python Package Index. Every spelling that
+/// normalizes to a valid version is accepted, eg. `v1.0-RC1`.
+fn is_pep440(version: &str) -> bool {
+    /// Splits off the leading ASCII digits
+    fn digits(s: &str) -> (&str, &str) {
+        s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()))
+    }
+
+    /// Strips an optional separator, one of the labels, and an optional number
+    fn label<'s>(s: &'s str, labels: &[&str]) -> Option<&'s str> {
+        let s = s.strip_prefix(['-', '_', '.']).unwrap_or(s);
+        let s = labels.iter().find_map(|label| s.strip_prefix(label))?;
+
+        // The number is optional, so a separator is only part of the label if
+        // it is followed by a number
+        let num = match s.strip_prefix(['-', '_', '.']) {
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+            _ => s,
+        };
+
+        Some(digits(num).1)
+    }
+
+    let version = version.to_ascii_lowercase();
+    let version = version.strip_prefix('v').unwrap_or(&version);
+
+    let (version, local) = match version.split_once('+') {
+        Some((version, local)) => (version, Some(local)),
+        None => (version, None),
+    };
+
+    if let Some(local) = local {
+        if local
+            .split(['-', '_', '.'])
+            .any(|seg| seg.is_empty() || !seg.bytes().all(|b| b.is_ascii_alphanumeric()))
+        {
+            return false;
+        }
+    }
+
+    let mut s = match version.split_once('!') {
+        Some((epoch, rest)) if !epoch.is_empty() && digits(epoch).1.is_empty() => rest,
+        Some(_) => return false,
+        None => version,
+    };
+
+    // The release, eg. 1.0.2
+    loop {
+        let (num, rest) = digits(s);
+        if num.is_empty() {
+            return false;
+        }
+
+        s = rest;
+        match s.strip_prefix('.') {
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => s = rest,
+            _ => break,
+        }
+    }
+
+    // The longer labels need to come first so they aren't partially matched
+    if let Some(rest) = label(s, &["alpha", "beta", "preview", "pre", "rc", "a", "b", "c"]) {
+        s = rest;
+    }
+
+    // Post releases can also be written as just `-N`
+    match s.strip_prefix('-').map(digits) {
+        Some((num, rest)) if !num.is_empty() => s = rest,
+        _ => {
+            if let Some(rest) = label(s, &["post", "rev", "r"]) {
+                s = rest;
+            }
+        }
+    }
+
+    if let Some(rest) = label(s, &["dev"]) {
+        s = rest;
+    }
+
+    s.is_empty()
+}
+
+/// The number of days in the month, taking leap years into account
+fn days_in_month(year: u32, month: u8) -> u8 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Parses a full, 40 character, git commit sha, in either case
+fn parse_sha(s: &str) -> Option<[u8; 20]> {
+    if s.len() != 40 || !s.is_ascii() {
+        return None;
+    }
+
+    let mut sha = [0; 20];
+    for (byte, hex) in sha.iter_mut().zip(s.as_bytes().chunks(2)) {
+        let hex = std::str::from_utf8(hex).ok()?;
+        *byte = u8::from_str_radix(hex, 16).ok()?;
+    }
+
+    Some(sha)
+}
+
+/// A simple, stable, 64-bit [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function)
+/// hash, used instead of the std hasher as the output needs to be the same
+/// across runs and versions of Rust
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 impl std::str::FromStr for Coordinate {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use anyhow::Context as _;
+        Self::from_str_with(s, ParsePolicy::Strict)
+    }
+}
+
+impl Coordinate {
+    /// Parses a coordinate, with unknown shapes and providers handled
+    /// according to the policy, the [`FromStr`] impl uses
+    /// [`ParsePolicy::Strict`]
+    pub fn from_str_with(s: &str, policy: ParsePolicy) -> Result<Self, Error> {
+        let coord = Self::parse_path(s, policy).map_err(|source| Error::ParseCoordinate {
+            input: s.to_owned(),
+            source,
+        })?;
+
+        coord.validate()?;
+        Ok(coord)
+    }
+
+    fn parse_path(s: &str, policy: ParsePolicy) -> Result<Self, CoordinateParseError> {
+        use CoordinateParseError as Cpe;
+
+        if s.ends_with('/') {
+            return Err(Cpe::TrailingSlash);
+        }
 
         let mut it = s.split('/');
+        let mut next = |segment: &'static str| match it.next() {
+            Some("") => Err(Cpe::EmptySegment(segment)),
+            Some(value) => Ok(value),
+            None => Err(Cpe::MissingSegment(segment)),
+        };
 
-        let shape = it.next().context("missing shape")?.parse()?;
-        let provider = it.next().context("missing provider")?.parse()?;
-        let namespace = match it.next().context("missing namespace")? {
-            "-" => None,
-            other => Some(other.to_owned()),
+        // Unknown shapes and providers are the only way parsing either can fail
+        let shape = next("shape")?;
+        let shape = Shape::from_str_with(shape, policy)
+            .map_err(|_err| Cpe::UnknownShape(shape.to_owned()))?;
+        let provider = next("provider")?;
+        let provider = Provider::from_str_with(provider, policy)
+            .map_err(|_err| Cpe::UnknownProvider(provider.to_owned()))?;
+        let (namespace, name) = match next("namespace")? {
+            "-" => (None, next("name")?),
+            // Allow the `-` to be omitted for providers that don't have
+            // namespaces, eg. `crate/cratesio/syn/1.0.14`
+            name if !provider.has_namespaces() => (None, name),
+            other => (Some(other), next("name")?),
         };
-        let name = it.next().context("missing name")?.to_owned();
-        let version = it.next().context("missing version")?.parse()?;
+        let version = next("version")?;
 
         let curation_pr = match it.next() {
-            Some("pr") => Some(
-                it.next()
-                    .context("expected curation PR number")?
-                    .parse()
-                    .context("unable to parse PR number")?,
-            ),
-            Some(other) => {
-                return Err(Error::Generic(anyhow::anyhow!(
-                    "unknown trailing path component '{}'",
-                    other
-                )));
+            Some("pr") => {
+                let pr = match it.next() {
+                    Some(pr) => pr,
+                    None => return Err(Cpe::MissingSegment("curation PR number")),
+                };
+                Some(pr.parse().map_err(|_err| Cpe::InvalidPr(pr.to_owned()))?)
             }
+            Some(other) => return Err(Cpe::TrailingSegment(other.to_owned())),
             None => None,
         };
 
+        if let Some(extra) = it.next() {
+            return Err(Cpe::TrailingSegment(extra.to_owned()));
+        }
+
+        let decode = |segment: &'static str, value: &str| {
+            decode_component(&shape, value).ok_or_else(|| Cpe::InvalidEncoding {
+                segment,
+                value: value.to_owned(),
+            })
+        };
+
         Ok(Self {
+            namespace: namespace.map(|ns| decode("namespace", ns)).transpose()?,
+            name: decode("name", name)?,
+            version: CoordVersion::for_shape(
+                &shape,
+                &decode_segment(version).map_err(|_err| Cpe::InvalidEncoding {
+                    segment: "version",
+                    value: version.to_owned(),
+                })?,
+            ),
             shape,
             provider,
-            namespace,
-            name,
-            version,
             curation_pr,
         })
     }
+
+    /// Parses a coordinate into its canonical form, so that the same component
+    /// is always stored the same way regardless of how it was written
+    ///
+    /// * The shape and provider are lowercased
+    /// * Names are normalized for providers whose names are case-insensitive,
+    ///   eg. crates.io and npm names are lowercased, and python package
+    ///   names are normalized the same as [`Coordinate::pypi`]
+    /// * A `v` prefix is stripped from versions that are otherwise valid
+    ///   semver, eg. `v1.0.0` becomes `1.0.0`
+    ///
+    /// Unknown shapes and providers are an error, the same as
+    /// [`ParsePolicy::Strict`]
+    pub fn from_str_canonical(s: &str) -> Result<Self, Error> {
+        let mut coord = Self::from_str_with(s, ParsePolicy::Strict)?;
+
+        match coord.provider {
+            Provider::CratesIo
+            | Provider::Npmjs
+            | Provider::Packagist
+            | Provider::Debian
+            | Provider::CondaForge
+            | Provider::AnacondaMain
+            | Provider::AnacondaR => {
+                coord.name = coord.name.to_lowercase();
+                coord.namespace = coord.namespace.map(|ns| ns.to_lowercase());
+            }
+            Provider::PyPi => coord.name = pep503_normalize(&coord.name),
+            Provider::Github
+            | Provider::GitLab
+            | Provider::MavenCentral
+            | Provider::MavenGoogle
+            | Provider::GradlePlugin
+            | Provider::RubyGems
+            | Provider::CocoaPods
+            | Provider::Golang
+            | Provider::NuGet
+            | Provider::Other(_) => {}
+        }
+
+        if let CoordVersion::Any(rev) = &coord.version {
+            coord.version = CoordVersion::from_str_lenient(rev);
+        }
+
+        Ok(coord)
+    }
 }
 
-impl fmt::Display for Coordinate {
+/// Displays the `type/provider/namespace/name/revision` path of a coordinate,
+/// without the curation PR
+pub(crate) struct CoordPath<'c> {
+    pub(crate) shape: &'c Shape,
+    pub(crate) provider: &'c Provider,
+    pub(crate) namespace: Option<&'c str>,
+    pub(crate) name: &'c str,
+    pub(crate) version: &'c CoordVersion,
+}
+
+impl<'c> From<&'c Coordinate> for CoordPath<'c> {
+    fn from(coord: &'c Coordinate) -> Self {
+        Self {
+            shape: &coord.shape,
+            provider: &coord.provider,
+            namespace: coord.namespace.as_deref(),
+            name: &coord.name,
+            version: &coord.version,
+        }
+    }
+}
+
+impl<'c> fmt::Display for CoordPath<'c> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Every segment needs to be percent-encoded, and Go module paths have
+        // an additional escaping scheme, see `encode_segment` and `go_escape`
+        let encode = |segment: &'c str| -> Cow<'c, str> {
+            if *self.shape == Shape::Go {
+                Cow::Owned(go_escape(segment))
+            } else {
+                encode_segment(segment)
+            }
+        };
+
         write!(
             f,
             "{}/{}/{}/{}/{}",
             self.shape.as_str(),
             self.provider.as_str(),
-            self.namespace.as_deref().unwrap_or("-"),
-            self.name,
-            self.version,
-        )?;
+            self.namespace.map_or(Cow::Borrowed("-"), encode),
+            encode(self.name),
+            encode_segment(&self.version.to_string()),
+        )
+    }
+}
+
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", CoordPath::from(self))?;
 
         if let Some(pr) = self.curation_pr {
             write!(f, "/pr/{}", pr)
@@ -322,10 +1700,51 @@ impl fmt::Display for Coordinate {
     }
 }
 
+/// Serializes the coordinate as its `type/provider/namespace/name/revision`
+/// path. Note that the curation PR, if any, is _not_ included, as it is not
+/// part of the identity of the component.
+impl serde::Serialize for Coordinate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&CoordPath::from(self))
+    }
+}
+
+/// Deserializes a coordinate from its string form, see the [`FromStr`] impl.
+/// Errors include the string that failed to parse.
+impl<'de> serde::Deserialize<'de> for Coordinate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(|err| match err {
+            // Already includes the coordinate that failed to parse
+            err @ Error::ParseCoordinate { .. } => serde::de::Error::custom(err),
+            Error::Generic(err) => {
+                serde::de::Error::custom(format_args!("invalid coordinate '{}': {:#}", s, err))
+            }
+            err => serde::de::Error::custom(format_args!("invalid coordinate '{}': {}", s, err)),
+        })
+    }
+}
+
 pub trait ApiResponse<B>: Sized + TryFrom<http::Response<B>, Error = Error>
 where
     B: AsRef<[u8]>,
 {
+    /// Parses the response if it has a successful status, otherwise returns
+    /// an [`Error::HttpStatus`].
+    ///
+    /// Note that any `2xx` status is considered a success, including
+    /// `206 Partial Content`, which the definitions endpoint can respond with
+    /// if some of the coordinates in a batch timed out on the server. In that
+    /// case the response only contains the definitions that were retrieved
+    /// in time, so use eg. [`GetResponse::missing`](crate::definitions::GetResponse::missing)
+    /// to determine which coordinates need to be requested again.
     fn try_from_parts(resp: http::response::Response<B>) -> Result<Self, Error> {
         if resp.status().is_success() {
             Self::try_from(resp)
@@ -350,3 +1769,23 @@ where
         }
     }
 }
+
+/// Strips the UTF-8 byte order mark that some misconfigured proxies prefix
+/// response bodies with, as `serde_json` will otherwise fail to parse them
+#[inline]
+pub(crate) fn strip_bom(body: &[u8]) -> &[u8] {
+    body.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(body)
+}
+
+/// Deserializes the first JSON value in a response body, ignoring any trailing
+/// bytes after it, as some misbehaving proxies append junk to the body, which
+/// would otherwise cause `serde_json` to fail. A leading BOM is also stripped.
+pub(crate) fn from_body<'de, T>(body: &'de [u8]) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    let mut deserializer = serde_json::Deserializer::from_slice(strip_bom(body));
+    // Note we intentionally don't call `end`, which is what fails if there
+    // are trailing bytes
+    Ok(T::deserialize(&mut deserializer)?)
+}
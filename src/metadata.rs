@@ -0,0 +1,35 @@
+use crate::{cargo, Coordinate, Error, SkippedPackage};
+use cargo_metadata::{Metadata, Package};
+
+impl TryFrom<&Package> for Coordinate {
+    type Error = Error;
+
+    /// Converts a package from crates.io into a `crate/cratesio/-/{name}/{version}`
+    /// coordinate, and a package from a GitHub repository into a
+    /// `git/github/{owner}/{repo}/{rev}` coordinate for the locked commit.
+    /// Path dependencies and packages from any other registry or git host
+    /// fail with [`Error::UnsupportedSource`].
+    fn try_from(pkg: &Package) -> Result<Self, Self::Error> {
+        package_coordinate(pkg).map_err(Error::UnsupportedSource)
+    }
+}
+
+#[inline]
+fn package_coordinate(pkg: &Package) -> Result<Coordinate, SkippedPackage> {
+    cargo::package_coordinate(
+        &pkg.name,
+        &pkg.version,
+        pkg.source.as_ref().map(|src| src.repr.as_str()),
+    )
+}
+
+/// Converts every package in the metadata into a coordinate, see the
+/// `TryFrom<&Package>` impl of [`Coordinate`] for how each package is
+/// converted.
+///
+/// The coordinates are sorted and deduplicated, as multiple packages can be
+/// built from the same git repository, and every package that was skipped is
+/// returned along with the reason it was skipped.
+pub fn coordinates_from_metadata(metadata: &Metadata) -> (Vec<Coordinate>, Vec<SkippedPackage>) {
+    cargo::partition(metadata.packages.iter().map(package_coordinate))
+}
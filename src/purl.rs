@@ -0,0 +1,123 @@
+use crate::{pep503_normalize, CoordVersion, Coordinate, Error, Provider, Shape};
+use packageurl::PackageUrl;
+
+/// The repository used in the `repository_url` qualifier of maven purls for
+/// packages from Google's Maven repository
+const MAVEN_GOOGLE: &str = "https://maven.google.com";
+
+impl TryFrom<&PackageUrl<'_>> for Coordinate {
+    type Error = Error;
+
+    /// Converts a [package URL](https://github.com/package-url/purl-spec), eg.
+    /// `pkg:cargo/serde@1.0.190`, into a coordinate.
+    ///
+    /// The `cargo`, `npm`, `pypi`, `maven`, `github`, `golang`, `gem`, and
+    /// `nuget` purl types are supported, any other type is an error. The purl
+    /// must have a version, and any subpath is ignored.
+    fn try_from(purl: &PackageUrl<'_>) -> Result<Self, Self::Error> {
+        let version = purl.version().ok_or_else(|| {
+            Error::Generic(anyhow::anyhow!("purl '{}' does not have a version", purl))
+        })?;
+
+        let name = purl.name().to_owned();
+        let (shape, provider, name) = match purl.ty() {
+            "cargo" => (Shape::Crate, Provider::CratesIo, name),
+            "npm" => (Shape::Npm, Provider::Npmjs, name),
+            "pypi" => (Shape::PyPi, Provider::PyPi, pep503_normalize(&name)),
+            "maven" => {
+                let provider = match purl.qualifiers().get("repository_url") {
+                    Some(url) if url.trim_end_matches('/') == MAVEN_GOOGLE => Provider::MavenGoogle,
+                    _ => Provider::MavenCentral,
+                };
+
+                (Shape::Maven, provider, name)
+            }
+            "github" => (Shape::Git, Provider::Github, name),
+            "golang" => (Shape::Go, Provider::Golang, name),
+            "gem" => (Shape::Gem, Provider::RubyGems, name),
+            "nuget" => (Shape::NuGet, Provider::NuGet, name),
+            other => {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "purl type '{}' has no equivalent coordinate",
+                    other
+                )));
+            }
+        };
+
+        let coord = Self {
+            version: CoordVersion::for_shape(&shape, version),
+            shape,
+            provider,
+            namespace: purl.namespace().map(String::from),
+            name,
+            curation_pr: None,
+        };
+
+        coord.validate()?;
+        Ok(coord)
+    }
+}
+
+impl Coordinate {
+    /// Parses a [package URL](https://github.com/package-url/purl-spec) string,
+    /// eg. `pkg:cargo/serde@1.0.190`, into a coordinate, see the
+    /// `TryFrom<&PackageUrl>` impl for which purls are supported.
+    pub fn from_purl(purl: &str) -> Result<Self, Error> {
+        let parsed: PackageUrl<'static> = purl.parse().map_err(|source| Error::ParsePurl {
+            input: purl.to_owned(),
+            source,
+        })?;
+
+        Self::try_from(&parsed)
+    }
+
+    /// Converts the coordinate into a [package URL](https://github.com/package-url/purl-spec),
+    /// eg. `pkg:cargo/serde@1.0.190`, the inverse of the `TryFrom<&PackageUrl>`
+    /// impl.
+    ///
+    /// Coordinates whose shape and provider have no equivalent purl type are an
+    /// error. The curation PR, if any, is not included.
+    pub fn to_purl(&self) -> Result<PackageUrl<'static>, Error> {
+        let ty = match (&self.shape, &self.provider) {
+            (Shape::Crate, Provider::CratesIo) => "cargo",
+            (Shape::Npm, Provider::Npmjs) => "npm",
+            (Shape::PyPi, Provider::PyPi) => "pypi",
+            (Shape::Maven, Provider::MavenCentral | Provider::MavenGoogle) => "maven",
+            (Shape::Git, Provider::Github) => "github",
+            (Shape::Go, Provider::Golang) => "golang",
+            (Shape::Gem, Provider::RubyGems) => "gem",
+            (Shape::NuGet, Provider::NuGet) => "nuget",
+            (shape, provider) => {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "'{}/{}' coordinates have no equivalent purl type",
+                    shape,
+                    provider
+                )));
+            }
+        };
+
+        let invalid = |err| {
+            Error::Generic(anyhow::anyhow!(
+                "unable to convert '{}' to a purl: {}",
+                self,
+                err
+            ))
+        };
+
+        let mut purl = PackageUrl::new(ty, self.name.clone()).map_err(invalid)?;
+
+        if let Some(namespace) = &self.namespace {
+            purl.with_namespace(namespace.clone()).map_err(invalid)?;
+        }
+
+        purl.with_version(self.version.to_string())
+            .map_err(invalid)?;
+
+        if self.provider == Provider::MavenGoogle {
+            purl.add_qualifier("repository_url", MAVEN_GOOGLE)
+                .map_err(invalid)?;
+        }
+
+        Ok(purl)
+    }
+}
@@ -0,0 +1,158 @@
+use crate::Coordinate;
+use std::{cmp::Ordering, collections::BTreeSet};
+
+/// A sorted set of unique coordinates
+///
+/// Coordinates that only differ by their [`curation_pr`](Coordinate::curation_pr)
+/// refer to the same component, so only the first one inserted is kept, eg.
+/// inserting `crate/cratesio/-/syn/1.0.14/pr/2` into a set that already
+/// contains `crate/cratesio/-/syn/1.0.14` does nothing.
+///
+/// The coordinates are always iterated in the same order, see the [`Ord`] impl
+/// of [`Coordinate`], so the requests created from a set, eg. with
+/// [`definitions::get`](crate::definitions::get), are deterministic. The set
+/// can be passed to `get` directly, or as `set.iter().cloned()` to keep it.
+///
+/// The set is serialized as a sequence of coordinates, which, the same as
+/// coordinates themselves, do not include the curation PR.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoordinateSet(BTreeSet<Entry>);
+
+/// Orders coordinates the same as [`Coordinate`], but ignoring the curation PR
+#[derive(Clone, Debug)]
+struct Entry(Coordinate);
+
+impl Entry {
+    #[inline]
+    fn key(&self) -> impl Ord + '_ {
+        let c = &self.0;
+        (&c.shape, &c.provider, &c.namespace, &c.name, &c.version)
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl CoordinateSet {
+    /// Creates an empty set
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the coordinate to the set, returning false if the set already
+    /// contained the same component, in which case the set is not modified
+    #[inline]
+    pub fn insert(&mut self, coord: Coordinate) -> bool {
+        self.0.insert(Entry(coord))
+    }
+
+    /// Returns true if the set contains the same component, regardless of
+    /// the curation PR
+    #[inline]
+    pub fn contains(&self, coord: &Coordinate) -> bool {
+        self.0.contains(&Entry(coord.clone()))
+    }
+
+    /// The number of coordinates in the set
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the set contains no coordinates
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the coordinates in sorted order
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Coordinate> + '_ {
+        self.0.iter().map(|entry| &entry.0)
+    }
+
+    /// Splits the sorted coordinates into chunks of at most `size`
+    /// coordinates, the last chunk may be smaller. The same set always
+    /// produces the same chunks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0
+    pub fn chunks(&self, size: usize) -> impl Iterator<Item = Vec<&Coordinate>> + '_ {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        let mut iter = self.iter();
+        std::iter::from_fn(move || {
+            let chunk: Vec<_> = iter.by_ref().take(size).collect();
+            (!chunk.is_empty()).then_some(chunk)
+        })
+    }
+}
+
+impl Extend<Coordinate> for CoordinateSet {
+    fn extend<I: IntoIterator<Item = Coordinate>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(Entry));
+    }
+}
+
+impl FromIterator<Coordinate> for CoordinateSet {
+    fn from_iter<I: IntoIterator<Item = Coordinate>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl IntoIterator for CoordinateSet {
+    type Item = Coordinate;
+    type IntoIter = Box<dyn Iterator<Item = Coordinate>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.0.into_iter().map(|entry| entry.0))
+    }
+}
+
+impl<'s> IntoIterator for &'s CoordinateSet {
+    type Item = &'s Coordinate;
+    type IntoIter = Box<dyn Iterator<Item = &'s Coordinate> + 's>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl serde::Serialize for CoordinateSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CoordinateSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        Vec::<Coordinate>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
@@ -0,0 +1,104 @@
+#![cfg(all(feature = "client", not(feature = "blocking")))]
+
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Spins up a server that responds to every request with an empty batch of
+/// definitions, taking longer to respond the more requests it is handling at
+/// once, returning the address it is listening on and the highest number of
+/// requests it handled concurrently
+fn serve_slowing(base_latency: Duration, fast_concurrency: usize) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    {
+        let peak = peak.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+
+                let in_flight = in_flight.clone();
+                let peak = peak.clone();
+                std::thread::spawn(move || {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut buf) {
+                            Ok(0) | Err(_) => return,
+                            Ok(read) => request.extend_from_slice(&buf[..read]),
+                        }
+                    }
+
+                    let concurrency = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(concurrency, Ordering::SeqCst);
+
+                    // Up to `fast_concurrency` requests are handled in the base
+                    // latency, after which the API slows down linearly
+                    let factor = std::cmp::max(concurrency, fast_concurrency) as u32;
+                    std::thread::sleep(base_latency * factor / fast_concurrency as u32);
+
+                    let body = "{}";
+                    let _res = write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+    }
+
+    (addr, peak)
+}
+
+#[tokio::test]
+async fn execute_concurrently() {
+    use cd::{client::ConcurrencyController, definitions};
+
+    let (addr, peak) = serve_slowing(Duration::from_millis(20), 4);
+
+    let options = definitions::GetOptions::default()
+        .chunk_size(1)
+        .base_uri(format!("http://{}", addr));
+    let requests: Vec<_> = definitions::get_with(
+        &options,
+        (0..200).map(|patch| cd::Coordinate::crates_io("syn", &semver::Version::new(1, 0, patch))),
+    )
+    .collect();
+    assert_eq!(200, requests.len());
+
+    // Anything handled by more than 8 requests at once is too slow
+    let mut controller = ConcurrencyController::new(1, 32, Duration::from_millis(40));
+
+    let client = cd::client::Client::new();
+    let results = client
+        .execute_concurrently::<definitions::GetResponse, _>(requests, &mut controller)
+        .await;
+
+    assert_eq!(200, results.len());
+    assert!(results.iter().all(|res| res.is_ok()));
+
+    // The limit grew from the minimum, but backed off before reaching the max
+    // once the API slowed down
+    assert!(controller.limit() > 1, "{}", controller.limit());
+    assert!(controller.limit() < 32, "{}", controller.limit());
+
+    let peak = peak.load(Ordering::SeqCst);
+    assert!(peak > 1, "{peak}");
+    assert!(peak <= 16, "{peak}");
+}
@@ -0,0 +1,384 @@
+#![cfg(feature = "blocking")]
+
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+};
+
+/// Spins up a server that responds to a single request with the specified
+/// JSON body, returning the address it is listening on and a handle that
+/// yields the raw request that was received
+fn serve(body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+    let (addr, handle) = serve_all(vec![body]);
+    (
+        addr,
+        std::thread::spawn(move || handle.join().unwrap().remove(0)),
+    )
+}
+
+/// Spins up a server that responds to each request in turn with the next of
+/// the specified JSON bodies, returning the address it is listening on and a
+/// handle that yields the raw requests that were received
+fn serve_all(bodies: Vec<&'static str>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    serve_responses(bodies.into_iter().map(|body| ("200 OK", body)).collect())
+}
+
+/// Same as [`serve_all`], but each response is sent with the specified status
+fn serve_responses(
+    responses: Vec<(&'static str, &'static str)>,
+) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::with_capacity(responses.len());
+
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => request.extend_from_slice(&buf[..read]),
+                }
+            }
+
+            // The client may have already hung up, eg. if it doesn't like the
+            // protocol we responded with
+            let _res = write!(
+                stream,
+                "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+
+            requests.push(String::from_utf8_lossy(&request).into_owned());
+        }
+
+        requests
+    });
+
+    (addr, handle)
+}
+
+#[test]
+fn with_http_client() {
+    let (addr, server) = serve(r#"["crate/cratesio/-/syn/1.0.14"]"#);
+
+    let http_client = reqwest::blocking::Client::builder()
+        .user_agent("my-org-agent/1.0")
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .unwrap();
+    let client = cd::client::Client::with_http_client(http_client);
+
+    let req = http::Request::builder()
+        .uri(format!("http://{}/definitions?pattern=syn", addr))
+        .body(bytes::Bytes::new())
+        .unwrap();
+
+    let search = client
+        .execute::<cd::definitions::SearchResponse>(req)
+        .unwrap();
+    assert_eq!("syn", search.coordinates[0].name);
+
+    let request = server.join().unwrap().to_lowercase();
+    assert!(request.contains("user-agent: my-org-agent/1.0"));
+}
+
+#[test]
+fn http_version() {
+    use cd::client::{Client, HttpVersion};
+
+    let request_with = |version| {
+        let (addr, server) = serve("[]");
+        let client = Client::with_http_version(version).unwrap();
+
+        let req = http::Request::builder()
+            .uri(format!("http://{}/definitions?pattern=syn", addr))
+            .body(bytes::Bytes::new())
+            .unwrap();
+
+        // The server only speaks HTTP/1.1 so this will fail for HTTP/2, we only
+        // care about what the client sent
+        let _res = client.execute::<cd::definitions::SearchResponse>(req);
+        server.join().unwrap()
+    };
+
+    let http1 = request_with(HttpVersion::Http1Only);
+    assert!(http1.starts_with("GET /definitions?pattern=syn HTTP/1.1\r\n"));
+
+    let http2 = request_with(HttpVersion::Http2PriorKnowledge);
+    assert!(http2.starts_with("PRI * HTTP/2.0\r\n"));
+}
+
+#[test]
+fn request_ids() {
+    fn request_id(client: &cd::client::Client) -> Option<String> {
+        let (addr, server) = serve("[]");
+
+        let req = http::Request::builder()
+            .uri(format!("http://{}/definitions?pattern=syn", addr))
+            .body(bytes::Bytes::new())
+            .unwrap();
+
+        client
+            .execute::<cd::definitions::SearchResponse>(req)
+            .unwrap();
+
+        server.join().unwrap().lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("x-request-id")
+                .then(|| value.trim().to_owned())
+        })
+    }
+
+    assert!(request_id(&cd::client::Client::new()).is_none());
+
+    let client = cd::client::Client::new().with_request_ids();
+    let first = request_id(&client).unwrap();
+    let second = request_id(&client).unwrap();
+    assert_ne!(first, second);
+    assert!(uuid::Uuid::parse_str(&first).is_ok());
+
+    let counter = std::sync::atomic::AtomicU32::new(0);
+    let client = cd::client::Client::new().with_request_id_generator(move || {
+        format!(
+            "req-{}",
+            counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        )
+    });
+    assert_eq!("req-0", request_id(&client).unwrap());
+    assert_eq!("req-1", request_id(&client).unwrap());
+}
+
+#[test]
+fn request_id_in_errors() {
+    let search = |client: &cd::client::Client, addr: &str| {
+        let req = http::Request::builder()
+            .uri(format!("http://{}/definitions?pattern=syn", addr))
+            .body(bytes::Bytes::new())
+            .unwrap();
+
+        match client.execute::<cd::definitions::SearchResponse>(req) {
+            Ok(_) => panic!("expected the request to fail"),
+            Err(err) => err,
+        }
+    };
+
+    let (addr, server) = serve_responses(vec![
+        ("429 Too Many Requests", "{}"),
+        ("429 Too Many Requests", "{}"),
+    ]);
+
+    // Without request ids, there is nothing to report
+    let err = search(&cd::client::Client::new(), &addr);
+    assert!(matches!(err, cd::Error::HttpStatus { .. }));
+    assert!(err.request_id().is_none());
+
+    let client = cd::client::Client::new().with_request_id_generator(|| "req-429".to_owned());
+    let err = search(&client, &addr);
+    assert!(matches!(
+        &err,
+        cd::Error::HttpStatus { source, request_id: Some(id) }
+            if source.0 == http::StatusCode::TOO_MANY_REQUESTS && id == "req-429"
+    ));
+    assert_eq!(
+        "HTTP status: 429 Too Many Requests (request id: req-429)",
+        err.to_string()
+    );
+
+    let requests = server.join().unwrap();
+    assert!(requests[1].to_lowercase().contains("x-request-id: req-429"));
+
+    // Nothing is listening on the address any longer, so sending the request
+    // fails
+    let client = cd::client::Client::new().with_request_id_generator(|| "req-conn".to_owned());
+    let err = search(&client, &addr);
+    assert!(matches!(
+        &err,
+        cd::Error::Reqwest { source, request_id: Some(id) }
+            if source.is_connect() && id == "req-conn"
+    ));
+    assert_eq!(Some("req-conn"), err.request_id());
+}
+
+#[test]
+fn adaptive_concurrency() {
+    use cd::client::ConcurrencyController;
+    use std::time::Duration;
+
+    let mut controller = ConcurrencyController::new(1, 32, Duration::from_secs(10));
+    assert_eq!(1, controller.limit());
+
+    // A mock API that handles up to 8 concurrent requests in 5 seconds, but
+    // slows down linearly as more requests are made concurrently
+    let latency =
+        |concurrency: usize| Duration::from_secs(5 * std::cmp::max(concurrency, 8) as u64 / 8);
+
+    let mut limits = Vec::new();
+    for _ in 0..100 {
+        let concurrency = controller.limit();
+        for _ in 0..concurrency {
+            controller.record_success(latency(concurrency));
+        }
+        limits.push(controller.limit());
+    }
+
+    // The limit should settle below the max, and never go above the point
+    // at which the API becomes too slow
+    let settled = &limits[50..];
+    assert!(settled.iter().all(|limit| *limit < 32));
+    assert!(settled.iter().all(|limit| *limit >= 8));
+    assert!(settled.iter().any(|limit| *limit > 8));
+
+    // Rate limiting and timeouts back off
+    let mut controller = ConcurrencyController::new(1, 32, Duration::from_secs(10));
+    for _ in 0..6 {
+        controller.record_success(Duration::from_secs(1));
+    }
+    assert_eq!(4, controller.limit());
+
+    controller.record_error(&cd::Error::from(http::StatusCode::TOO_MANY_REQUESTS));
+    assert_eq!(2, controller.limit());
+
+    // But other errors don't
+    controller.record_error(&cd::Error::from(http::StatusCode::NOT_FOUND));
+    assert_eq!(2, controller.limit());
+
+    // Every retryable status is a signal of overload, not just 429
+    for status in [
+        http::StatusCode::INTERNAL_SERVER_ERROR,
+        http::StatusCode::SERVICE_UNAVAILABLE,
+        http::StatusCode::GATEWAY_TIMEOUT,
+    ] {
+        let mut controller = ConcurrencyController::new(1, 32, Duration::from_secs(10));
+        for _ in 0..6 {
+            controller.record_success(Duration::from_secs(1));
+        }
+
+        assert!(cd::error::is_retryable_status(status));
+        controller.record_error(&cd::Error::from(status));
+        assert_eq!(2, controller.limit(), "{status}");
+    }
+}
+
+#[test]
+fn all_revisions() {
+    let (addr, server) = serve_all(vec![
+        r#"{"data":[{"coordinates":{"type":"crate","provider":"cratesio","name":"syn","revision":"1.0.14"}},{"coordinates":{"type":"crate","provider":"cratesio","name":"syn","revision":"1.0.15"}}],"continuationToken":"page-2"}"#,
+        r#"{"data":[{"coordinates":{"type":"crate","provider":"cratesio","name":"syn","revision":"2.0.0"}}]}"#,
+    ]);
+
+    let options = cd::definitions::GetOptions::default().base_uri(format!("http://{}", addr));
+    let revisions = cd::client::Client::new()
+        .all_revisions_with(
+            &options,
+            cd::Shape::Crate,
+            cd::Provider::CratesIo,
+            None,
+            "syn",
+        )
+        .unwrap();
+
+    assert_eq!(
+        ["1.0.14", "1.0.15", "2.0.0"],
+        revisions
+            .iter()
+            .map(|coord| coord.version.to_string())
+            .collect::<Vec<_>>()
+            .as_slice()
+    );
+    assert!(revisions.iter().all(|coord| coord.name == "syn"));
+
+    let requests = server.join().unwrap();
+    assert!(requests[0]
+        .starts_with("GET /definitions?type=crate&provider=cratesio&name=syn HTTP/1.1\r\n"));
+    assert!(requests[1].starts_with(
+        "GET /definitions?type=crate&provider=cratesio&name=syn&continuationToken=page-2 HTTP/1.1\r\n"
+    ));
+}
+
+#[test]
+fn all_revisions_full_coordinates() {
+    // The results are parsed the same as the coordinates they correspond to,
+    // eg. git revisions are commit shas and go namespaces are decoded
+    let (addr, server) = serve_all(vec![
+        r#"{"data":[{"coordinates":{"type":"git","provider":"github","namespace":"dtolnay","name":"syn","revision":"855F331CF0E14916A1C3026786B59E6F6B6F2D6F"}}]}"#,
+        r#"{"data":[{"coordinates":{"type":"go","provider":"golang","namespace":"github.com%2fstretchr","name":"testify","revision":"v1.8.0"}}]}"#,
+    ]);
+
+    let options = cd::definitions::GetOptions::default().base_uri(format!("http://{}", addr));
+    let client = cd::client::Client::new();
+
+    let git = client
+        .all_revisions_with(
+            &options,
+            cd::Shape::Git,
+            cd::Provider::Github,
+            Some("dtolnay"),
+            "syn",
+        )
+        .unwrap();
+    let expected: cd::Coordinate =
+        "git/github/dtolnay/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f"
+            .parse()
+            .unwrap();
+    assert!(matches!(git[0].version, cd::CoordVersion::GitSha(_)));
+    assert!(git[0].validate().is_ok());
+    assert_eq!(vec![expected], git);
+
+    let go = client
+        .all_revisions_with(
+            &options,
+            cd::Shape::Go,
+            cd::Provider::Golang,
+            Some("github.com/stretchr"),
+            "testify",
+        )
+        .unwrap();
+    let expected: cd::Coordinate = "go/golang/github.com%2fstretchr/testify/v1.8.0"
+        .parse()
+        .unwrap();
+    assert_eq!(Some("github.com/stretchr"), go[0].namespace.as_deref());
+    assert_eq!(
+        "go/golang/github.com%2fstretchr/testify/v1.8.0",
+        go[0].to_string()
+    );
+    assert_eq!(vec![expected], go);
+
+    let requests = server.join().unwrap();
+    assert!(requests[1].starts_with(
+        "GET /definitions?type=go&provider=golang&namespace=github.com%2Fstretchr&name=testify HTTP/1.1\r\n"
+    ));
+}
+
+#[test]
+fn all_revisions_repeated_token() {
+    // A misbehaving API that returns a token it already returned would
+    // otherwise be followed forever
+    let (addr, server) = serve_all(vec![
+        r#"{"data":[{"coordinates":{"type":"crate","provider":"cratesio","name":"syn","revision":"1.0.14"}}],"continuationToken":"page-2"}"#,
+        r#"{"data":[{"coordinates":{"type":"crate","provider":"cratesio","name":"syn","revision":"1.0.15"}}],"continuationToken":"page-3"}"#,
+        r#"{"data":[{"coordinates":{"type":"crate","provider":"cratesio","name":"syn","revision":"1.0.16"}}],"continuationToken":"page-2"}"#,
+    ]);
+
+    let options = cd::definitions::GetOptions::default().base_uri(format!("http://{}", addr));
+    let err = cd::client::Client::new()
+        .all_revisions_with(
+            &options,
+            cd::Shape::Crate,
+            cd::Provider::CratesIo,
+            None,
+            "syn",
+        )
+        .unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains("'page-2' was returned more than once"));
+    assert_eq!(3, server.join().unwrap().len());
+}
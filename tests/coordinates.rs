@@ -0,0 +1,1695 @@
+use cd::Coordinate;
+
+#[test]
+fn redacted() {
+    let syn: Coordinate = "crate/cratesio/-/syn/1.0.14".parse().unwrap();
+    assert_eq!("crate/cratesio/-/syn/1.0.14", syn.redacted());
+
+    let private: Coordinate =
+        "git/github/embark/secret-sauce/855f331cf0e14916a1c3026786b59e6f6b6f2d6f"
+            .parse()
+            .unwrap();
+    let redacted = private.redacted();
+
+    assert!(redacted.starts_with("git/github/"));
+    assert!(redacted.ends_with("/855f331cf0e14916a1c3026786b59e6f6b6f2d6f"));
+    assert!(!redacted.contains("embark"));
+    assert!(!redacted.contains("secret-sauce"));
+    // The redaction is stable so that the same component can be correlated
+    assert_eq!(redacted, private.redacted());
+}
+
+#[test]
+fn version_valid_for_provider() {
+    let valid = |s: &str| {
+        s.parse::<Coordinate>()
+            .unwrap()
+            .version_valid_for_provider()
+    };
+
+    assert!(valid("crate/cratesio/-/syn/1.0.14"));
+    assert!(valid("crate/cratesio/-/syn/1.0.0-rc.1"));
+    assert!(!valid("crate/cratesio/-/syn/1.0"));
+    assert!(!valid("crate/cratesio/-/syn/1.0.x"));
+    assert!(valid(
+        "git/github/dtolnay/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f"
+    ));
+
+    // PyPI versions aren't semver, but must be PEP 440 versions, including
+    // the alternative spellings that normalize to one
+    for version in [
+        "2.31.0",
+        "1.0",
+        "2024.1",
+        "1!2.0",
+        "1.0a1",
+        "1.0b",
+        "1.0rc1",
+        "1.0-RC.1",
+        "1.0.post1",
+        "1.0-1",
+        "1.0r2",
+        "1.0.dev0",
+        "1.0a1.post2.dev3",
+        "1.0+ubuntu.1",
+        "v1.0",
+    ] {
+        assert!(
+            valid(&format!("pypi/pypi/-/requests/{version}")),
+            "{version}"
+        );
+    }
+
+    for version in [
+        "lastest", "1.0.x", "1..0", "1.0.", ".1", "1.0+", "1.0+a..b", "!1.0", "1.0-", "1.0rc-",
+        "1.0foo",
+    ] {
+        assert!(
+            !valid(&format!("pypi/pypi/-/requests/{version}")),
+            "{version}"
+        );
+    }
+}
+
+#[cfg(feature = "cargo")]
+#[test]
+fn from_cargo_lock() {
+    use cd::UnsupportedSource;
+
+    const LOCK: &str = r#"
+# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 3
+
+[[package]]
+name = "cd"
+version = "0.3.0"
+dependencies = [
+ "syn",
+ "tame-gcs",
+]
+
+[[package]]
+name = "proc-macro2"
+version = "1.0.86"
+source = "sparse+https://index.crates.io/"
+checksum = "5e719e8df665df0d1c8fbfd238015744736151d4445ec0836b8e628aae103b77"
+
+[[package]]
+name = "syn"
+version = "1.0.14"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "af6f3550d8dff9ef7dc34d384ac6f107e5d31c8f57d9f28e0081503f547ac8f5"
+
+[[package]]
+name = "tame-gcs"
+version = "0.4.0"
+source = "git+https://github.com/EmbarkStudios/tame-gcs?rev=abc123#abc123def456"
+"#;
+
+    let (coords, skipped) = Coordinate::from_cargo_lock(LOCK).unwrap();
+
+    assert_eq!(
+        vec![
+            "crate/cratesio/-/proc-macro2/1.0.86".to_owned(),
+            "crate/cratesio/-/syn/1.0.14".to_owned(),
+        ],
+        coords.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+    );
+
+    // Git dependencies must be locked to a full commit sha
+    assert_eq!(
+        vec![
+            ("cd", UnsupportedSource::Path),
+            (
+                "tame-gcs",
+                UnsupportedSource::Git(
+                    "https://github.com/EmbarkStudios/tame-gcs?rev=abc123".to_owned()
+                )
+            ),
+        ],
+        skipped
+            .iter()
+            .map(|pkg| (pkg.name.as_str(), pkg.reason.clone()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[cfg(feature = "cargo")]
+#[test]
+fn from_cargo_lock_v4() {
+    use cd::UnsupportedSource;
+
+    const LOCK: &str = r#"
+# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "cargo-platform",
+ "cargo-util",
+ "internal",
+ "itoa",
+ "itoa 0.4.8",
+]
+
+[[package]]
+name = "cargo-platform"
+version = "0.1.8"
+source = "git+https://github.com/rust-lang/cargo?branch=feature%2Fplatform#A9A6C2D6B29B7D1B58A1AD2FE1A4D9E8C2B4A1F0"
+
+[[package]]
+name = "cargo-util"
+version = "0.2.14"
+source = "git+https://github.com/rust-lang/cargo?branch=feature%2Fplatform#A9A6C2D6B29B7D1B58A1AD2FE1A4D9E8C2B4A1F0"
+
+[[package]]
+name = "internal"
+version = "2.1.0"
+source = "sparse+https://cargo.example.com/index/"
+checksum = "0f2c59bd51be0e2bb6bdbf04ff3d6a93a5cbb32a0f2c59bd51be0e2bb6bdbf04"
+
+[[package]]
+name = "itoa"
+version = "0.4.8"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "b71991ff56294aa922b450139ee08b3bfc70982c6b2c7562771375cf73542dd4"
+
+[[package]]
+name = "itoa"
+version = "1.0.18"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "8eaf4bc02d17cbdd7ff4c7438cafcdf7fb9a4613313ad11b4f8fefe7d3fa0c68"
+"#;
+
+    let (coords, skipped) = Coordinate::from_cargo_lock(LOCK).unwrap();
+
+    // Both crates from the cargo repo are built from the same commit, and the
+    // order is deterministic
+    assert_eq!(
+        vec![
+            "crate/cratesio/-/itoa/0.4.8".to_owned(),
+            "crate/cratesio/-/itoa/1.0.18".to_owned(),
+            "git/github/rust-lang/cargo/a9a6c2d6b29b7d1b58a1ad2fe1a4d9e8c2b4a1f0".to_owned(),
+        ],
+        coords.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![
+            ("app", UnsupportedSource::Path),
+            (
+                "internal",
+                UnsupportedSource::Registry("sparse+https://cargo.example.com/index/".to_owned())
+            ),
+        ],
+        skipped
+            .iter()
+            .map(|pkg| (pkg.name.as_str(), pkg.reason.clone()))
+            .collect::<Vec<_>>()
+    );
+
+    let dir = std::env::temp_dir().join(format!("cd-lockfile-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Cargo.lock");
+    std::fs::write(&path, LOCK).unwrap();
+    let from_path = Coordinate::from_cargo_lock_path(&path);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(coords, from_path.unwrap().0);
+
+    assert!(Coordinate::from_cargo_lock_path(dir.join("Cargo.lock")).is_err());
+}
+
+#[test]
+fn latest() {
+    let latest = Coordinate::latest(cd::Shape::Crate, cd::Provider::CratesIo, None, "syn");
+    assert_eq!("crate/cratesio/-/syn/latest", latest.to_string());
+    assert!(latest.to_string().ends_with("/latest"));
+
+    // The latest pseudo-revision is valid for the registries that resolve it,
+    // even those that otherwise require semver or PEP 440 versions
+    for (shape, provider, namespace) in [
+        (cd::Shape::Crate, cd::Provider::CratesIo, None),
+        (cd::Shape::Npm, cd::Provider::Npmjs, Some("@types")),
+        (cd::Shape::PyPi, cd::Provider::PyPi, None),
+        (
+            cd::Shape::Maven,
+            cd::Provider::MavenCentral,
+            Some("org.apache"),
+        ),
+    ] {
+        let latest = Coordinate::latest(shape, provider, namespace, "syn");
+        assert!(latest.version_valid_for_provider(), "{latest}");
+
+        let parsed: Coordinate = latest.to_string().parse().unwrap();
+        assert_eq!(latest, parsed);
+        assert!(parsed.version_valid_for_provider(), "{parsed}");
+    }
+}
+
+#[test]
+fn optional_namespace() {
+    let strict: Coordinate = "crate/cratesio/-/syn/1.0.14".parse().unwrap();
+    let short: Coordinate = "crate/cratesio/syn/1.0.14".parse().unwrap();
+
+    assert_eq!(strict, short);
+    assert_eq!(None, short.namespace);
+    assert_eq!("syn", short.name);
+    assert_eq!("crate/cratesio/-/syn/1.0.14", short.to_string());
+
+    let pr: Coordinate = "crate/cratesio/syn/1.0.14/pr/12".parse().unwrap();
+    assert_eq!(Some(12), pr.curation_pr);
+
+    // Providers with namespaces still require all of the segments
+    assert!("git/github/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f"
+        .parse::<Coordinate>()
+        .is_err());
+}
+
+#[test]
+fn provider_aliases() {
+    use cd::Provider;
+
+    for (alias, canonical) in [
+        ("cratesio", Provider::CratesIo),
+        ("crates.io", Provider::CratesIo),
+        ("crates-io", Provider::CratesIo),
+        ("github", Provider::Github),
+        ("github.com", Provider::Github),
+        ("npmjs", Provider::Npmjs),
+        ("npm", Provider::Npmjs),
+    ] {
+        let provider: Provider = alias.parse().unwrap();
+        assert_eq!(canonical, provider);
+    }
+
+    let coord: Coordinate = "crate/crates.io/-/syn/1.0.14".parse().unwrap();
+    assert_eq!("crate/cratesio/-/syn/1.0.14", coord.to_string());
+}
+
+#[test]
+fn provider_display_name() {
+    use cd::Provider;
+
+    let expected = [
+        (Provider::CratesIo, "crates.io"),
+        (Provider::Github, "GitHub"),
+        (Provider::GitLab, "GitLab"),
+        (Provider::Npmjs, "npm Registry"),
+        (Provider::MavenCentral, "Maven Central"),
+        (Provider::MavenGoogle, "Google Maven"),
+        (Provider::GradlePlugin, "Gradle Plugin Portal"),
+        (Provider::PyPi, "PyPI"),
+        (Provider::RubyGems, "RubyGems"),
+        (Provider::CocoaPods, "CocoaPods"),
+        (Provider::Packagist, "Packagist"),
+        (Provider::Debian, "Debian"),
+        (Provider::Golang, "Go Module Proxy"),
+        (Provider::CondaForge, "conda-forge"),
+        (Provider::AnacondaMain, "Anaconda (main)"),
+        (Provider::AnacondaR, "Anaconda (R)"),
+        (Provider::NuGet, "NuGet Gallery"),
+    ];
+
+    // Every known provider must have a display name, so new providers need
+    // to be added here
+    assert_eq!(Provider::ALL.len(), expected.len());
+
+    for provider in Provider::ALL {
+        let name = provider.display_name();
+        assert!(!name.is_empty(), "{provider}");
+
+        let (_, expected) = expected
+            .iter()
+            .find(|(p, _)| p == provider)
+            .unwrap_or_else(|| panic!("no expected display name for {provider}"));
+        assert_eq!(*expected, name, "{provider}");
+    }
+}
+
+#[test]
+fn short_hash() {
+    let syn: Coordinate = "crate/cratesio/-/syn/1.0.14".parse().unwrap();
+
+    let hash = syn.short_hash();
+    assert_eq!(8, hash.len());
+    assert_eq!(hash, syn.short_hash());
+    // The hash must not change between runs or versions
+    assert_eq!("2d61b8a9", hash);
+
+    let other: Coordinate = "crate/cratesio/-/syn/1.0.15".parse().unwrap();
+    assert_ne!(hash, other.short_hash());
+}
+
+#[test]
+fn name_similarity() {
+    let serde: Coordinate = "crate/cratesio/-/serde/1.0.104".parse().unwrap();
+
+    assert_eq!(1.0, serde.name_similarity("serde"));
+    assert_eq!(1.0, serde.name_similarity("Serde"));
+
+    // A single transposition is one edit out of 5 characters
+    let sedre = serde.name_similarity("sedre");
+    assert!(sedre > 0.75 && sedre < 1.0, "{sedre}");
+    assert!((sedre - 0.8).abs() < f32::EPSILON);
+
+    // As is a single substitution
+    assert!((serde.name_similarity("serda") - 0.8).abs() < f32::EPSILON);
+
+    assert!(serde.name_similarity("tokio") < 0.25);
+    assert_eq!(0.0, serde.name_similarity(""));
+}
+
+#[test]
+fn version_as_date() {
+    use cd::{definitions::Date, CoordVersion};
+
+    let date = |s: &str| s.parse::<CoordVersion>().unwrap().as_date();
+    let ymd = |year, month, day| Some(Date { year, month, day });
+
+    assert_eq!(ymd(2021, 3, 14), date("20210314T101500Z"));
+    assert_eq!(ymd(2021, 3, 14), date("2021-03-14"));
+    assert_eq!(ymd(2019, 12, 1), date("snapshot.2019.12.01"));
+    assert_eq!(ymd(2020, 1, 31), date("1.2-git20200131"));
+
+    assert_eq!(None, date("855f331cf0e14916a1c3026786b59e6f6b6f2d6f"));
+    assert_eq!(None, date("latest"));
+    assert_eq!(None, date("20211399"));
+    assert_eq!(None, date("2021-02-29"));
+    assert_eq!(None, date("2021-04-31"));
+    assert_eq!(None, date("1900-02-29"));
+    assert_eq!(ymd(2020, 2, 29), date("2020-02-29"));
+    assert_eq!(ymd(2000, 2, 29), date("20000229"));
+    // Semver versions are never dates
+    assert_eq!(None, date("2021.3.14"));
+}
+
+#[test]
+fn component_urls() {
+    let url = |s: &str| s.parse::<Coordinate>().unwrap().provider_url();
+
+    let syn: Coordinate = "crate/cratesio/-/syn/1.0.14".parse().unwrap();
+    assert_eq!(
+        "https://clearlydefined.io/definitions/crate/cratesio/-/syn/1.0.14",
+        syn.clearlydefined_url()
+    );
+    assert_eq!(
+        Some("https://crates.io/crates/syn/1.0.14"),
+        syn.provider_url().as_deref()
+    );
+
+    assert_eq!(
+        Some("https://github.com/dtolnay/syn/tree/855f331cf0e14916a1c3026786b59e6f6b6f2d6f"),
+        url("git/github/dtolnay/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f").as_deref()
+    );
+    assert_eq!(
+        Some("https://www.npmjs.com/package/@types/node/v/18.11.9"),
+        url("npm/npmjs/@types/node/18.11.9").as_deref()
+    );
+    assert_eq!(
+        Some("https://central.sonatype.com/artifact/org.apache.commons/commons-lang3/3.12.0"),
+        url("maven/mavencentral/org.apache.commons/commons-lang3/3.12.0").as_deref()
+    );
+    assert_eq!(
+        Some("https://pkg.go.dev/github.com/BurntSushi/toml@v1.2.1"),
+        url("go/golang/github.com%2f!burnt!sushi/toml/v1.2.1").as_deref()
+    );
+
+    // Debian binary packages don't have a page of their own
+    assert_eq!(None, url("deb/debian/-/zlib1g/1:1.2.11.dfsg-2_amd64"));
+
+    let unknown =
+        Coordinate::from_str_with("hex/hexpm/-/phoenix/1.7.0", cd::ParsePolicy::Lenient).unwrap();
+    assert_eq!(None, unknown.provider_url());
+}
+
+#[test]
+fn from_url() {
+    let syn: Coordinate = "crate/cratesio/-/syn/1.0.14".parse().unwrap();
+    assert_eq!(
+        syn,
+        Coordinate::from_url("https://clearlydefined.io/definitions/crate/cratesio/-/syn/1.0.14")
+            .unwrap()
+    );
+    assert_eq!(syn, Coordinate::from_url(&syn.api_url()).unwrap());
+    assert_eq!(
+        syn,
+        Coordinate::from_url(&syn.clearlydefined_url()).unwrap()
+    );
+
+    let node: Coordinate = "npm/npmjs/@types/node/14.0.0".parse().unwrap();
+    for url in [
+        "https://api.clearlydefined.io/definitions/npm/npmjs/@types/node/14.0.0",
+        "https://api.clearlydefined.io/definitions/npm/npmjs/%40types/node/14.0.0",
+        "https://clearlydefined.io/definitions/npm/npmjs/%40types/node/14.0.0",
+    ] {
+        assert_eq!(node, Coordinate::from_url(url).unwrap());
+    }
+
+    assert_eq!(
+        Some("github.com/stretchr"),
+        Coordinate::from_url(
+            "https://clearlydefined.io/definitions/go/golang/github.com%2fstretchr/testify/v1.8.0"
+        )
+        .unwrap()
+        .namespace
+        .as_deref()
+    );
+
+    let curated = Coordinate::from_url(
+        "https://api.clearlydefined.io/definitions/crate/cratesio/-/syn/1.0.14/pr/12",
+    )
+    .unwrap();
+    assert_eq!(Some(12), curated.curation_pr);
+
+    let err = |url: &str| Coordinate::from_url(url).unwrap_err().to_string();
+    assert_eq!(
+        "other error: 'https://crates.io/crates/syn/1.0.14' is not a clearlydefined.io url",
+        err("https://crates.io/crates/syn/1.0.14")
+    );
+    assert_eq!(
+        "other error: 'https://api.clearlydefined.io/harvest/crate/cratesio/-/syn/1.0.14' is not a definition url",
+        err("https://api.clearlydefined.io/harvest/crate/cratesio/-/syn/1.0.14")
+    );
+}
+
+#[test]
+fn api_url() {
+    let syn: Coordinate = "crate/cratesio/-/syn/1.0.14".parse().unwrap();
+    assert_eq!(
+        "https://api.clearlydefined.io/definitions/crate/cratesio/-/syn/1.0.14",
+        syn.api_url()
+    );
+
+    let curated: Coordinate = "crate/cratesio/-/syn/1.0.14/pr/123".parse().unwrap();
+    assert_eq!(
+        "https://api.clearlydefined.io/definitions/crate/cratesio/-/syn/1.0.14/pr/123",
+        curated.api_url()
+    );
+}
+
+#[test]
+fn npm() {
+    use cd::{Provider, Shape};
+
+    let scoped: Coordinate = "npm/npmjs/@types/node/14.0.0".parse().unwrap();
+    assert_eq!(Shape::Npm, scoped.shape);
+    assert_eq!(Provider::Npmjs, scoped.provider);
+    assert_eq!(Some("@types"), scoped.namespace.as_deref());
+    assert_eq!("node", scoped.name);
+    assert_eq!("npm/npmjs/@types/node/14.0.0", scoped.to_string());
+
+    let unscoped: Coordinate = "npm/npmjs/-/lodash/4.17.21".parse().unwrap();
+    assert_eq!(None, unscoped.namespace);
+    assert_eq!("lodash", unscoped.name);
+    assert_eq!("npm/npmjs/-/lodash/4.17.21", unscoped.to_string());
+
+    for coord in [scoped, unscoped] {
+        let round_tripped: Coordinate = coord.to_string().parse().unwrap();
+        assert_eq!(coord, round_tripped);
+    }
+}
+
+#[test]
+fn maven() {
+    use cd::{Provider, Shape};
+
+    let coord: Coordinate = "maven/mavencentral/org.apache.commons/commons-lang3/3.12.0"
+        .parse()
+        .unwrap();
+    assert_eq!(Shape::Maven, coord.shape);
+    assert_eq!(Provider::MavenCentral, coord.provider);
+    assert_eq!(Some("org.apache.commons"), coord.namespace.as_deref());
+    assert_eq!("commons-lang3", coord.name);
+    assert_eq!(
+        "maven/mavencentral/org.apache.commons/commons-lang3/3.12.0",
+        coord.to_string()
+    );
+
+    // The groupId is required
+    assert!("maven/mavencentral/-/commons-lang3/3.12.0"
+        .parse::<Coordinate>()
+        .is_err());
+}
+
+#[test]
+fn parse_policy() {
+    use cd::{ParsePolicy, Provider, Shape};
+
+    let unknown = "hex/hexpm/-/phoenix/1.7.0";
+
+    // Strict is the default
+    assert!(unknown.parse::<Coordinate>().is_err());
+    assert!(Coordinate::from_str_with(unknown, ParsePolicy::Strict).is_err());
+
+    let coord = Coordinate::from_str_with(unknown, ParsePolicy::Lenient).unwrap();
+    assert_eq!(Shape::Other("hex".to_owned()), coord.shape);
+    assert_eq!(Provider::Other("hexpm".to_owned()), coord.provider);
+    assert_eq!("phoenix", coord.name);
+    assert_eq!(unknown, coord.to_string());
+    assert_eq!(
+        coord,
+        Coordinate::from_str_with(&coord.to_string(), ParsePolicy::Lenient).unwrap()
+    );
+
+    assert_eq!("hex", coord.shape.to_string());
+    assert_eq!("hexpm", coord.provider.to_string());
+
+    // Known shapes and providers are still strongly typed
+    let coord =
+        Coordinate::from_str_with("crate/cratesio/-/syn/1.0.14", ParsePolicy::Lenient).unwrap();
+    assert_eq!(Shape::Crate, coord.shape);
+    assert_eq!(Provider::CratesIo, coord.provider);
+}
+
+#[test]
+fn gradle_plugin() {
+    use cd::{Provider, Shape};
+
+    let plugin =
+        "maven/gradleplugin/org.jetbrains.kotlin.jvm/org.jetbrains.kotlin.jvm.gradle.plugin/1.9.0";
+    let coord: Coordinate = plugin.parse().unwrap();
+    assert_eq!(Shape::Maven, coord.shape);
+    assert_eq!(Provider::GradlePlugin, coord.provider);
+    assert_eq!(Some("org.jetbrains.kotlin.jvm"), coord.namespace.as_deref());
+    assert_eq!("org.jetbrains.kotlin.jvm.gradle.plugin", coord.name);
+    assert_eq!(plugin, coord.to_string());
+
+    let round_tripped: Coordinate = coord.to_string().parse().unwrap();
+    assert_eq!(coord, round_tripped);
+
+    // Gradle plugins are always maven packages
+    assert!("npm/gradleplugin/-/left-pad/1.3.0"
+        .parse::<Coordinate>()
+        .is_err());
+}
+
+#[test]
+fn pypi() {
+    assert_eq!(
+        "pypi/pypi/-/pillow/9.4.0",
+        Coordinate::pypi("Pillow", "9.4.0").to_string()
+    );
+    assert_eq!(
+        "pypi/pypi/-/typing-extensions/4.4.0",
+        Coordinate::pypi("typing_extensions", "4.4.0").to_string()
+    );
+    assert_eq!(
+        "pypi/pypi/-/zope-interface/5.5.2",
+        Coordinate::pypi("zope.interface", "5.5.2").to_string()
+    );
+    assert_eq!(
+        "pypi/pypi/-/django/4.1",
+        Coordinate::pypi("Django", "4.1").to_string()
+    );
+    // Runs of separators are collapsed
+    assert_eq!("a-b", Coordinate::pypi("A._-B", "1.0.0").name);
+
+    let parsed: Coordinate = "pypi/pypi/-/zope-interface/5.5.2".parse().unwrap();
+    assert_eq!(Coordinate::pypi("zope.interface", "5.5.2"), parsed);
+}
+
+#[test]
+fn cocoapods() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    let coord: Coordinate = "pod/cocoapods/-/Alamofire/5.6.4".parse().unwrap();
+    assert_eq!(Shape::Pod, coord.shape);
+    assert_eq!(Provider::CocoaPods, coord.provider);
+    assert_eq!(None, coord.namespace);
+    assert_eq!("Alamofire", coord.name);
+    assert!(matches!(coord.version, CoordVersion::Semver(_)));
+
+    // Some pods have 4 component versions, which aren't semver
+    let four = "pod/cocoapods/-/GoogleUtilities/7.10.0.1";
+    let coord: Coordinate = four.parse().unwrap();
+    assert_eq!(CoordVersion::Any("7.10.0.1".to_owned()), coord.version);
+    assert_eq!(four, coord.to_string());
+    assert_eq!(coord, coord.to_string().parse().unwrap());
+}
+
+#[test]
+fn composer() {
+    use cd::{Provider, Shape};
+
+    let console = "composer/packagist/symfony/console/6.2.0";
+    let coord: Coordinate = console.parse().unwrap();
+    assert_eq!(Shape::Composer, coord.shape);
+    assert_eq!(Provider::Packagist, coord.provider);
+    assert_eq!(Some("symfony"), coord.namespace.as_deref());
+    assert_eq!("console", coord.name);
+    assert_eq!(console, coord.to_string());
+    assert_eq!(coord, coord.to_string().parse().unwrap());
+
+    // Vendors can contain dots and dashes
+    let coord: Coordinate = "composer/packagist/php-http/message-factory/1.0.2"
+        .parse()
+        .unwrap();
+    assert_eq!(Some("php-http"), coord.namespace.as_deref());
+    let coord: Coordinate = "composer/packagist/my.vendor/package/1.0.0"
+        .parse()
+        .unwrap();
+    assert_eq!(Some("my.vendor"), coord.namespace.as_deref());
+
+    // The vendor is required
+    assert!("composer/packagist/-/console/6.2.0"
+        .parse::<Coordinate>()
+        .is_err());
+}
+
+#[test]
+fn debian() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    for deb in [
+        "deb/debian/-/zlib1g/1:1.2.11.dfsg-2_amd64",
+        "deb/debian/-/libc6/2.31-13+deb11u5_arm64",
+        "deb/debian/-/gcc-12-base/12.2.0-14~bpo11+1_i386",
+    ] {
+        let coord: Coordinate = deb.parse().unwrap();
+        assert_eq!(Shape::Deb, coord.shape);
+        assert_eq!(Provider::Debian, coord.provider);
+        assert_eq!(None, coord.namespace);
+
+        let revision = deb.rsplit('/').next().unwrap();
+        assert_eq!(CoordVersion::Any(revision.to_owned()), coord.version);
+        assert_eq!(deb, coord.to_string());
+        assert_eq!(coord, coord.to_string().parse().unwrap());
+    }
+}
+
+#[test]
+fn debian_sources() {
+    use cd::{Provider, Shape};
+
+    for debsrc in [
+        "debsrc/debian/-/curl/7.74.0-1.3+deb11u7",
+        "debsrc/debian/-/zlib/1:1.2.11.dfsg-2",
+        "debsrc/debian/-/openssl/3.0.11-1~deb12u2",
+    ] {
+        let coord: Coordinate = debsrc.parse().unwrap();
+        assert_eq!(Shape::DebSrc, coord.shape);
+        assert_eq!(Provider::Debian, coord.provider);
+        assert_eq!(None, coord.namespace);
+
+        // Some revisions happen to also be valid semver, but the revision
+        // must still be written exactly as it was parsed
+        let revision = debsrc.rsplit('/').next().unwrap();
+        assert_eq!(revision, coord.version.to_string());
+        assert_eq!(debsrc, coord.to_string());
+        assert_eq!(coord, coord.to_string().parse().unwrap());
+    }
+
+    assert!("debsrc/github/-/curl/7.74.0-1.3+deb11u7"
+        .parse::<Coordinate>()
+        .is_err());
+}
+
+#[test]
+fn canonical() {
+    let canonical = |s: &str| Coordinate::from_str_canonical(s).unwrap().to_string();
+
+    assert_eq!(
+        "crate/cratesio/-/serde/1.0.0",
+        canonical("Crate/CratesIO/-/Serde/v1.0.0")
+    );
+    assert_eq!(
+        "crate/cratesio/-/serde/1.0.0",
+        canonical("crate/crates.io/-/serde/1.0.0")
+    );
+    assert_eq!(
+        "npm/npmjs/@types/node/18.11.9",
+        canonical("NPM/npmjs/@Types/Node/V18.11.9")
+    );
+    assert_eq!(
+        "pypi/pypi/-/zope-interface/5.5.2",
+        canonical("pypi/PyPI/-/Zope.Interface/5.5.2")
+    );
+
+    // Names are case sensitive for some providers
+    assert_eq!(
+        "git/github/EmbarkStudios/cargo-deny/3b1b1f9e0c3b5f8d9a1e4c6b7d2f0a8e9c1b3d5f",
+        canonical("Git/GitHub/EmbarkStudios/cargo-deny/3b1b1f9e0c3b5f8d9a1e4c6b7d2f0a8e9c1b3d5f")
+    );
+    assert_eq!(
+        "maven/mavencentral/org.apache.commons/commons-Lang3/3.12.0",
+        canonical("maven/MavenCentral/org.apache.commons/commons-Lang3/3.12.0")
+    );
+
+    // Only versions that are semver once the prefix is removed are changed
+    assert_eq!(
+        "gem/rubygems/-/rails/version-7",
+        canonical("gem/rubygems/-/rails/version-7")
+    );
+
+    // Plain parsing is unchanged
+    assert_eq!(
+        "crate/cratesio/-/Serde/v1.0.0",
+        "crate/cratesio/-/Serde/v1.0.0"
+            .parse::<Coordinate>()
+            .unwrap()
+            .to_string()
+    );
+    assert!(Coordinate::from_str_canonical("Hex/HexPM/-/phoenix/1.7.0").is_err());
+}
+
+#[test]
+fn source_archive() {
+    use cd::{Provider, Shape};
+
+    let coord: Coordinate = "sourcearchive/mavencentral/org.apache.commons/commons-lang3/3.12.0"
+        .parse()
+        .unwrap();
+    assert_eq!(Shape::SourceArchive, coord.shape);
+    assert_eq!(Provider::MavenCentral, coord.provider);
+    assert_eq!(Some("org.apache.commons"), coord.namespace.as_deref());
+    assert_eq!(
+        "sourcearchive/mavencentral/org.apache.commons/commons-lang3/3.12.0",
+        coord.to_string()
+    );
+
+    assert_eq!(
+        "sourcearchive/npmjs/-/left-pad/1.3.0",
+        "sourcearchive/npmjs/-/left-pad/1.3.0"
+            .parse::<Coordinate>()
+            .unwrap()
+            .to_string()
+    );
+
+    for unsupported in [
+        "sourcearchive/cratesio/-/syn/1.0.14",
+        "sourcearchive/github/dtolnay/syn/1.0.14",
+    ] {
+        assert!(unsupported.parse::<Coordinate>().is_err(), "{unsupported}");
+    }
+}
+
+#[test]
+fn go() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    let testify: Coordinate = "go/golang/github.com%2fstretchr/testify/v1.8.0"
+        .parse()
+        .unwrap();
+    assert_eq!(Shape::Go, testify.shape);
+    assert_eq!(Provider::Golang, testify.provider);
+    assert_eq!(Some("github.com/stretchr"), testify.namespace.as_deref());
+    assert_eq!("testify", testify.name);
+    assert_eq!(CoordVersion::Any("v1.8.0".to_owned()), testify.version);
+    assert_eq!(
+        "go/golang/github.com%2fstretchr/testify/v1.8.0",
+        testify.to_string()
+    );
+
+    // Uppercase letters are escaped by the module proxy
+    let toml = Coordinate {
+        shape: Shape::Go,
+        provider: Provider::Golang,
+        namespace: Some("github.com/BurntSushi".to_owned()),
+        name: "toml".to_owned(),
+        version: CoordVersion::Any("v1.2.1".to_owned()),
+        curation_pr: None,
+    };
+    assert_eq!(
+        "go/golang/github.com%2f!burnt!sushi/toml/v1.2.1",
+        toml.to_string()
+    );
+    assert_eq!(toml, toml.to_string().parse().unwrap());
+
+    // Multi-segment namespaces, with the separator in either case
+    let sdk: Coordinate = "go/golang/github.com%2FAzure%2Fazure-sdk-for-go%2fsdk/azcore/v1.4.0"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        Some("github.com/Azure/azure-sdk-for-go/sdk"),
+        sdk.namespace.as_deref()
+    );
+    assert_eq!(
+        "go/golang/github.com%2f!azure%2fazure-sdk-for-go%2fsdk/azcore/v1.4.0",
+        sdk.to_string()
+    );
+
+    assert!("go/golang/github.com%2f!Azure/azcore/v1.4.0"
+        .parse::<Coordinate>()
+        .is_err());
+    assert!("go/golang/github.com%2fazure/azcore!/v1.4.0"
+        .parse::<Coordinate>()
+        .is_err());
+}
+
+#[test]
+fn conda() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    for (conda, provider, subdir, revision) in [
+        (
+            "conda/conda-forge/linux-64/numpy/1.26.4-py311h64a7726_0",
+            Provider::CondaForge,
+            "linux-64",
+            "1.26.4-py311h64a7726_0",
+        ),
+        (
+            "conda/conda-forge/noarch/requests/2.31.0-pyhd8ed1ab_0",
+            Provider::CondaForge,
+            "noarch",
+            "2.31.0-pyhd8ed1ab_0",
+        ),
+        (
+            "conda/anaconda-main/osx-arm64/zlib/1.2.13-h5a0b063_0",
+            Provider::AnacondaMain,
+            "osx-arm64",
+            "1.2.13-h5a0b063_0",
+        ),
+        (
+            "conda/anaconda-r/win-64/r-base/4.2.0-h3e8b5a1_1",
+            Provider::AnacondaR,
+            "win-64",
+            "4.2.0-h3e8b5a1_1",
+        ),
+    ] {
+        let coord: Coordinate = conda.parse().unwrap();
+        assert_eq!(Shape::Conda, coord.shape);
+        assert_eq!(provider, coord.provider);
+        assert_eq!(Some(subdir), coord.namespace.as_deref());
+
+        // The build string means the revision is never semver
+        assert_eq!(CoordVersion::Any(revision.to_owned()), coord.version);
+        assert_eq!(conda, coord.to_string());
+    }
+}
+
+#[test]
+fn conda_sources() {
+    use cd::{Provider, Shape};
+
+    let coord: Coordinate = "condasrc/conda-forge/-/numpy/1.26.4".parse().unwrap();
+    assert_eq!(Shape::CondaSrc, coord.shape);
+    assert_eq!(Provider::CondaForge, coord.provider);
+    assert_eq!(None, coord.namespace);
+    assert_eq!("condasrc/conda-forge/-/numpy/1.26.4", coord.to_string());
+
+    assert_eq!(
+        "condasrc/anaconda-main/-/zlib/1.2.13",
+        "condasrc/anaconda-main/-/zlib/1.2.13"
+            .parse::<Coordinate>()
+            .unwrap()
+            .to_string()
+    );
+
+    assert!("condasrc/pypi/-/numpy/1.26.4"
+        .parse::<Coordinate>()
+        .is_err());
+}
+
+#[test]
+fn gitlab() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    const SHA: &str = "9c1f3a5e7b9d1f3a5c7e9b1d3f5a7c9e1b3d5f7a";
+
+    let coord: Coordinate = format!("git/gitlab/gitlab-org/gitaly/{SHA}")
+        .parse()
+        .unwrap();
+    assert_eq!(Shape::Git, coord.shape);
+    assert_eq!(Provider::GitLab, coord.provider);
+    assert_eq!(Some("gitlab-org"), coord.namespace.as_deref());
+    assert_eq!(
+        format!("git/gitlab/gitlab-org/gitaly/{SHA}"),
+        coord.to_string()
+    );
+
+    // Nested groups are percent-encoded in the namespace
+    let nested = Coordinate {
+        shape: Shape::Git,
+        provider: Provider::GitLab,
+        namespace: Some("group/subgroup".to_owned()),
+        name: "fork".to_owned(),
+        version: CoordVersion::for_shape(&Shape::Git, SHA),
+        curation_pr: None,
+    };
+    assert_eq!(
+        format!("git/gitlab/group%2fsubgroup/fork/{SHA}"),
+        nested.to_string()
+    );
+    assert_eq!(nested, nested.to_string().parse().unwrap());
+    assert_eq!(
+        nested,
+        format!("git/gitlab/group%2Fsubgroup/fork/{SHA}")
+            .parse()
+            .unwrap()
+    );
+
+    assert!("sourcearchive/gitlab/group%2fsubgroup/fork/1.0.0"
+        .parse::<Coordinate>()
+        .is_ok());
+    assert!("crate/gitlab/-/fork/1.0.0".parse::<Coordinate>().is_err());
+}
+
+#[test]
+fn validate() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    let err = |s: &str| s.parse::<Coordinate>().unwrap_err().to_string();
+
+    assert!(err("crate/github/-/serde/1.0.0")
+        .contains("the 'crate' shape is not supported by the 'github' provider"));
+    assert!(err("maven/mavencentral/-/commons-lang3/3.12.0")
+        .contains("maven coordinates require a namespace (the groupId)"));
+    assert!(err("git/github/serde-rs/serde/v1.0.0")
+        .contains("git revisions must be a full 40 character commit sha, not 'v1.0.0'"));
+
+    // Coordinates constructed manually can be validated as well
+    let mut coord = Coordinate {
+        shape: Shape::Npm,
+        provider: Provider::CratesIo,
+        namespace: None,
+        name: "left-pad".to_owned(),
+        version: CoordVersion::Semver(semver::Version::new(1, 3, 0)),
+        curation_pr: None,
+    };
+    assert!(coord.validate().is_err());
+    coord.provider = Provider::Npmjs;
+    coord.validate().unwrap();
+
+    coord.shape = Shape::Crate;
+    coord.provider = Provider::CratesIo;
+    coord.namespace = Some("serde-rs".to_owned());
+    assert_eq!(
+        "other error: the 'cratesio' provider does not have namespaces, but 'serde-rs' was specified",
+        coord.validate().unwrap_err().to_string()
+    );
+
+    // Unknown shapes and providers can't be checked
+    let lenient = |s: &str| Coordinate::from_str_with(s, cd::ParsePolicy::Lenient);
+    lenient("crate/crates.example.com/-/serde/1.0.0").unwrap();
+    lenient("hex/cratesio/-/serde/1.0.0").unwrap();
+}
+
+#[test]
+fn all_variants() {
+    use cd::{ParsePolicy, Provider, Shape};
+
+    for shape in Shape::ALL {
+        assert_eq!(shape, &shape.as_str().parse::<Shape>().unwrap());
+    }
+
+    for provider in Provider::ALL {
+        assert_eq!(provider, &provider.as_str().parse::<Provider>().unwrap());
+    }
+
+    // Parsing ignores case, but the canonical form is always lowercase
+    assert_eq!(Shape::Crate, "Crate".parse().unwrap());
+    assert_eq!(Shape::Git, "GIT".parse().unwrap());
+    assert_eq!(Provider::CratesIo, "CratesIo".parse().unwrap());
+    assert_eq!(Provider::Github, "GitHub.com".parse().unwrap());
+    assert_eq!(
+        "crate/cratesio/-/syn/1.0.14",
+        "Crate/CratesIo/-/syn/1.0.14"
+            .parse::<Coordinate>()
+            .unwrap()
+            .to_string()
+    );
+
+    // Unknown values are kept as they were written
+    assert_eq!(
+        Shape::Other("Hex".to_owned()),
+        Shape::from_str_with("Hex", ParsePolicy::Lenient).unwrap()
+    );
+}
+
+#[test]
+fn eq_hash_ord() {
+    use std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        hash::{Hash, Hasher},
+    };
+
+    let a: Coordinate = "crate/cratesio/-/syn/1.0.14".parse().unwrap();
+    let b: Coordinate = "crate/cratesio/syn/1.0.14".parse().unwrap();
+    assert_eq!(a, b);
+
+    let hash = |coord: &Coordinate| {
+        let mut hasher = DefaultHasher::new();
+        coord.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(&a), hash(&b));
+
+    let set: HashSet<_> = [a.clone(), b, a.clone()].into_iter().collect();
+    assert_eq!(1, set.len());
+
+    let mut coords: Vec<Coordinate> = [
+        "crate/cratesio/-/syn/1.0.14",
+        "crate/cratesio/-/syn/1.0.9",
+        "crate/cratesio/-/syn/1.0.14-alpha.1",
+        "crate/cratesio/-/anyhow/1.0.70",
+        "npm/npmjs/-/left-pad/1.3.0",
+        "crate/cratesio/-/syn/latest",
+    ]
+    .iter()
+    .map(|c| c.parse().unwrap())
+    .collect();
+    coords.sort();
+
+    // Semver versions are compared by precedence, not as strings
+    assert_eq!(
+        [
+            "crate/cratesio/-/anyhow/1.0.70",
+            "crate/cratesio/-/syn/1.0.9",
+            "crate/cratesio/-/syn/1.0.14-alpha.1",
+            "crate/cratesio/-/syn/1.0.14",
+            "crate/cratesio/-/syn/latest",
+            "npm/npmjs/-/left-pad/1.3.0",
+        ]
+        .as_slice(),
+        coords.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn serialize() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    assert_eq!("\"crate\"", serde_json::to_string(&Shape::Crate).unwrap());
+    assert_eq!(
+        "\"mavengoogle\"",
+        serde_json::to_string(&Provider::MavenGoogle).unwrap()
+    );
+    assert_eq!(
+        "\"1.0.14\"",
+        serde_json::to_string(&CoordVersion::Semver(semver::Version::new(1, 0, 14))).unwrap()
+    );
+
+    for coord in [
+        "crate/cratesio/-/syn/1.0.14",
+        "npm/npmjs/@types/node/18.11.9",
+        "git/github/dtolnay/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f",
+    ] {
+        let parsed: Coordinate = coord.parse().unwrap();
+        let json = serde_json::to_string(&parsed).unwrap();
+        assert_eq!(format!("\"{coord}\""), json);
+
+        let round_tripped: String = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, round_tripped.parse().unwrap());
+        assert_eq!(parsed, serde_json::from_str::<Coordinate>(&json).unwrap());
+    }
+
+    // The curation PR is not part of the serialized form
+    let curated: Coordinate = "crate/cratesio/-/syn/1.0.14/pr/12".parse().unwrap();
+    assert_eq!(
+        "\"crate/cratesio/-/syn/1.0.14\"",
+        serde_json::to_string(&curated).unwrap()
+    );
+}
+
+#[test]
+fn deserialize() {
+    #[derive(serde::Deserialize)]
+    struct Config {
+        deps: Vec<Coordinate>,
+    }
+
+    let config: Config = serde_json::from_str(
+        r#"{ "deps": ["crate/cratesio/-/syn/1.0.14", "npm/npmjs/@types/node/18.11.9"] }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        vec![
+            "crate/cratesio/-/syn/1.0.14".parse::<Coordinate>().unwrap(),
+            "npm/npmjs/@types/node/18.11.9".parse().unwrap(),
+        ],
+        config.deps
+    );
+
+    let err = serde_json::from_str::<Config>(
+        r#"{ "deps": [
+            "crate/cratesio/-/syn/1.0.14",
+            "crate/cratesio/-/serde/1.0.190/pr/nope"
+        ] }"#,
+    )
+    .err()
+    .unwrap();
+
+    // serde_json appends the location of the error
+    assert!(err.is_data());
+    assert!(err.to_string().starts_with(
+        "invalid coordinate 'crate/cratesio/-/serde/1.0.190/pr/nope': invalid curation PR number 'nope' at line "
+    ));
+}
+
+#[test]
+fn builder() {
+    use cd::{CoordVersion, Error, Provider, Shape};
+
+    let serde = Coordinate::builder(Shape::Crate, Provider::CratesIo)
+        .name("serde")
+        .version("1.0.190")
+        .build()
+        .unwrap();
+    assert_eq!("crate/cratesio/-/serde/1.0.190", serde.to_string());
+
+    assert_eq!(
+        serde,
+        Coordinate::builder(Shape::Crate, Provider::CratesIo)
+            .name("serde")
+            .version(semver::Version::new(1, 0, 190))
+            .build()
+            .unwrap()
+    );
+
+    let commons = Coordinate::builder(Shape::Maven, Provider::MavenCentral)
+        .namespace("org.apache.commons")
+        .name("commons-lang3")
+        .version(CoordVersion::Any("3.12.0".to_owned()))
+        .curation_pr(7)
+        .build()
+        .unwrap();
+    assert_eq!(
+        "maven/mavencentral/org.apache.commons/commons-lang3/3.12.0/pr/7",
+        commons.to_string()
+    );
+
+    assert!(matches!(
+        Coordinate::builder(Shape::Crate, Provider::CratesIo)
+            .version("1.0.190")
+            .build(),
+        Err(Error::MissingField("name"))
+    ));
+    assert!(matches!(
+        Coordinate::builder(Shape::Crate, Provider::CratesIo)
+            .name("serde")
+            .build(),
+        Err(Error::MissingField("version"))
+    ));
+
+    // Namespaces must match the provider
+    assert!(Coordinate::builder(Shape::Crate, Provider::CratesIo)
+        .namespace("serde-rs")
+        .name("serde")
+        .version("1.0.190")
+        .build()
+        .is_err());
+    assert!(Coordinate::builder(Shape::Maven, Provider::MavenCentral)
+        .name("commons-lang3")
+        .version("3.12.0")
+        .build()
+        .is_err());
+
+    // As must the version
+    assert_eq!(
+        "other error: version 'latest-ish' is not valid for the 'cratesio' provider",
+        Coordinate::builder(Shape::Crate, Provider::CratesIo)
+            .name("serde")
+            .version("latest-ish")
+            .build()
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[cfg(feature = "cargo-metadata")]
+#[test]
+fn cargo_metadata() {
+    use cd::{Error, SkippedPackage, UnsupportedSource};
+
+    let metadata: cargo_metadata::Metadata =
+        serde_json::from_str(include_str!("data/cargo-metadata.json")).unwrap();
+
+    let (coords, skipped) = cd::coordinates_from_metadata(&metadata);
+
+    // Both crates from the cargo repo are built from the same commit
+    assert_eq!(
+        [
+            "crate/cratesio/-/itoa/1.0.18",
+            "crate/cratesio/-/serde/1.0.190",
+            "git/github/rust-lang/cargo/a9a6c2d6b29b7d1b58a1ad2fe1a4d9e8c2b4a1f0",
+        ]
+        .as_slice(),
+        coords.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+    );
+
+    let skipped_pkg = |name: &str, version: &str, reason| SkippedPackage {
+        name: name.to_owned(),
+        version: version.parse().unwrap(),
+        reason,
+    };
+
+    assert_eq!(
+        vec![
+            skipped_pkg(
+                "fork",
+                "0.4.0",
+                UnsupportedSource::Git("https://gitlab.com/group/fork".to_owned())
+            ),
+            skipped_pkg("app", "0.1.0", UnsupportedSource::Path),
+            skipped_pkg("local-util", "0.1.0", UnsupportedSource::Path),
+            skipped_pkg(
+                "internal",
+                "2.1.0",
+                UnsupportedSource::Registry("sparse+https://cargo.example.com/index/".to_owned())
+            ),
+        ],
+        skipped
+    );
+
+    let pkg = |name: &str| {
+        metadata
+            .packages
+            .iter()
+            .find(|pkg| pkg.name == name)
+            .unwrap()
+    };
+
+    let platform = Coordinate::try_from(pkg("cargo-platform")).unwrap();
+    assert_eq!(
+        Some("rust-lang"),
+        platform.namespace.as_deref(),
+        "{platform}"
+    );
+    assert!(platform.version.as_sha().is_some());
+
+    match Coordinate::try_from(pkg("local-util")) {
+        Err(err @ Error::UnsupportedSource(_)) => assert_eq!(
+            "unable to create a coordinate for 'local-util 0.1.0': path dependencies are not supported",
+            err.to_string()
+        ),
+        other => panic!("unexpected result {other:?}"),
+    }
+}
+
+#[cfg(feature = "purl")]
+#[test]
+fn purl() {
+    for (purl, coord) in [
+        ("pkg:cargo/serde@1.0.190", "crate/cratesio/-/serde/1.0.190"),
+        ("pkg:npm/left-pad@1.3.0", "npm/npmjs/-/left-pad/1.3.0"),
+        (
+            "pkg:npm/%40types/node@14.0.0",
+            "npm/npmjs/@types/node/14.0.0",
+        ),
+        (
+            "pkg:pypi/zope-interface@5.5.2",
+            "pypi/pypi/-/zope-interface/5.5.2",
+        ),
+        (
+            "pkg:maven/org.apache.commons/commons-lang3@3.12.0",
+            "maven/mavencentral/org.apache.commons/commons-lang3/3.12.0",
+        ),
+        (
+            "pkg:maven/androidx.core/core@1.9.0?repository_url=https://maven.google.com",
+            "maven/mavengoogle/androidx.core/core/1.9.0",
+        ),
+        (
+            "pkg:github/dtolnay/syn@855f331cf0e14916a1c3026786b59e6f6b6f2d6f",
+            "git/github/dtolnay/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f",
+        ),
+        (
+            "pkg:golang/github.com/stretchr/testify@v1.8.0",
+            "go/golang/github.com%2fstretchr/testify/v1.8.0",
+        ),
+        ("pkg:gem/rails@7.0.4", "gem/rubygems/-/rails/7.0.4"),
+        (
+            "pkg:nuget/Newtonsoft.Json@13.0.3",
+            "nuget/nuget/-/Newtonsoft.Json/13.0.3",
+        ),
+    ] {
+        let purl: packageurl::PackageUrl = purl.parse().unwrap();
+        let parsed = Coordinate::try_from(&purl).unwrap();
+        assert_eq!(coord, parsed.to_string());
+        assert_eq!(purl, parsed.to_purl().unwrap());
+
+        assert_eq!(parsed, Coordinate::from_purl(&purl.to_string()).unwrap());
+    }
+
+    // Unencoded npm scopes and names that aren't normalized are also accepted
+    assert_eq!(
+        "npm/npmjs/@types/node/14.0.0",
+        Coordinate::from_purl("pkg:npm/@types/node@14.0.0")
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(
+        "pypi/pypi/-/zope-interface/5.5.2",
+        Coordinate::from_purl("pkg:pypi/Zope.Interface@5.5.2#src")
+            .unwrap()
+            .to_string()
+    );
+
+    let err = |purl: &str| Coordinate::from_purl(purl).unwrap_err().to_string();
+    assert_eq!(
+        "other error: purl type 'hex' has no equivalent coordinate",
+        err("pkg:hex/phoenix@1.7.0")
+    );
+    assert_eq!(
+        "other error: purl 'pkg:cargo/serde' does not have a version",
+        err("pkg:cargo/serde")
+    );
+    // The scope of an npm package is not mistaken for the version
+    assert_eq!(
+        "other error: purl 'pkg:npm/%40types/node' does not have a version",
+        err("pkg:npm/@types/node")
+    );
+    assert_eq!(
+        "invalid purl 'cargo/serde@1.0.190': missing scheme",
+        err("cargo/serde@1.0.190")
+    );
+
+    let debian: Coordinate = "deb/debian/-/zlib1g/1:1.2.11.dfsg-2_amd64".parse().unwrap();
+    assert_eq!(
+        "other error: 'deb/debian' coordinates have no equivalent purl type",
+        debian.to_purl().unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn percent_encoding() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    // Go namespaces contain `/`, which must be encoded so the namespace stays a
+    // single segment
+    let testify = Coordinate {
+        shape: Shape::Go,
+        provider: Provider::Golang,
+        namespace: Some("github.com/stretchr".to_owned()),
+        name: "testify".to_owned(),
+        version: CoordVersion::Any("v1.8.0".to_owned()),
+        curation_pr: None,
+    };
+    assert_eq!(
+        "go/golang/github.com%2fstretchr/testify/v1.8.0",
+        testify.to_string()
+    );
+    assert_eq!(testify, testify.to_string().parse().unwrap());
+
+    // npm scopes are left as they are, but are decoded if they are encoded
+    let node: Coordinate = "npm/npmjs/@types/node/14.0.0".parse().unwrap();
+    assert_eq!("npm/npmjs/@types/node/14.0.0", node.to_string());
+    assert_eq!(
+        node,
+        "npm/npmjs/%40types/node/14.0.0"
+            .parse::<Coordinate>()
+            .unwrap()
+    );
+
+    // As are the `+` and `~` in debian revisions
+    let curl: Coordinate = "debsrc/debian/-/curl/7.74.0-1.3+deb11u7~bpo1"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        "debsrc/debian/-/curl/7.74.0-1.3+deb11u7~bpo1",
+        curl.to_string()
+    );
+
+    // Any character that would break the path or a URI is encoded
+    let odd = Coordinate {
+        shape: Shape::Git,
+        provider: Provider::GitLab,
+        namespace: Some("my group/sub?group#1".to_owned()),
+        name: "100%".to_owned(),
+        version: CoordVersion::for_shape(&Shape::Git, "855f331cf0e14916a1c3026786b59e6f6b6f2d6f"),
+        curation_pr: Some(2),
+    };
+    let encoded = odd.to_string();
+    assert_eq!(
+        "git/gitlab/my%20group%2fsub%3fgroup%231/100%25/855f331cf0e14916a1c3026786b59e6f6b6f2d6f/pr/2",
+        encoded
+    );
+    assert_eq!(odd, encoded.parse().unwrap());
+    assert!(format!("{}/definitions/{}", cd::ROOT_URI, encoded)
+        .parse::<http::Uri>()
+        .is_ok());
+}
+
+#[test]
+fn version_ordering() {
+    use cd::CoordVersion;
+
+    let v = |s: &str| s.parse::<CoordVersion>().unwrap();
+
+    assert!(v("1.2.0") < v("1.10.0"));
+    assert!(v("1.0.0-alpha") < v("1.0.0-alpha.1"));
+    assert!(v("1.0.0-alpha.1") < v("1.0.0-beta"));
+    assert!(v("1.0.0-beta") < v("1.0.0"));
+
+    // Other revisions are compared as strings
+    assert!(v("2021-01-07") < v("2023-03-20"));
+    assert!(v("abc") < v("abd"));
+
+    // Semver versions are always before other revisions
+    assert!(v("99.0.0") < v("0.1"));
+    assert!(v("1.0.0") < v("latest"));
+
+    // The order doesn't depend on the order the versions started in
+    let mut versions = [
+        v("latest"),
+        v("1.10.0"),
+        v("v1.8.0"),
+        v("1.0.0"),
+        v("1.0.0-alpha"),
+        v("1.2.0"),
+        v("855f331cf0e14916a1c3026786b59e6f6b6f2d6f"),
+    ];
+    let expected = [
+        "1.0.0-alpha",
+        "1.0.0",
+        "1.2.0",
+        "1.10.0",
+        "855f331cf0e14916a1c3026786b59e6f6b6f2d6f",
+        "latest",
+        "v1.8.0",
+    ];
+
+    versions.sort();
+    assert_eq!(
+        expected.as_slice(),
+        versions.iter().map(|v| v.to_string()).collect::<Vec<_>>()
+    );
+
+    versions.reverse();
+    versions.sort();
+    assert_eq!(
+        expected.as_slice(),
+        versions.iter().map(|v| v.to_string()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn version_matches() {
+    use cd::CoordVersion;
+
+    let strict = |s: &str| s.parse::<CoordVersion>().unwrap();
+
+    // The prefix is only stripped when parsing leniently
+    assert_eq!(CoordVersion::Any("v1.8.0".to_owned()), strict("v1.8.0"));
+    assert_eq!(strict("1.8.0"), CoordVersion::from_str_lenient("v1.8.0"));
+    assert_eq!(strict("1.8.0"), CoordVersion::from_str_lenient("V1.8.0"));
+    assert_eq!(
+        CoordVersion::Any("version-2".to_owned()),
+        CoordVersion::from_str_lenient("version-2")
+    );
+
+    assert!(strict("v1.8.0").matches(&strict("1.8.0")));
+    assert!(strict("1.8.0").matches(&strict("v1.8.0")));
+    assert!(strict("1.8.0+build.5").matches(&strict("1.8.0")));
+    assert!(strict("latest").matches(&strict("latest")));
+
+    assert!(!strict("1.8.0-rc.1").matches(&strict("1.8.0")));
+    assert!(!strict("v1.8.1").matches(&strict("1.8.0")));
+    assert!(!strict("latest").matches(&strict("1.8.0")));
+}
+
+#[test]
+fn git_sha() {
+    use cd::{CoordVersion, Provider, Shape};
+
+    const SHA: &str = "855f331cf0e14916a1c3026786b59e6f6b6f2d6f";
+
+    let syn: Coordinate = format!("git/github/dtolnay/syn/{SHA}").parse().unwrap();
+    let sha = syn.version.as_sha().expect("expected a commit sha");
+    assert_eq!(&[0x85, 0x5f, 0x33, 0x1c], &sha[..4]);
+    assert_eq!("855f331", syn.version.short());
+    assert_eq!(format!("git/github/dtolnay/syn/{SHA}"), syn.to_string());
+    assert_eq!(syn, syn.to_string().parse().unwrap());
+
+    // Uppercase hex is accepted, but always displayed as lowercase
+    let upper: Coordinate = format!("git/github/dtolnay/syn/{}", SHA.to_uppercase())
+        .parse()
+        .unwrap();
+    assert_eq!(syn, upper);
+    assert_eq!(syn.to_string(), upper.to_string());
+
+    // Short shas, and anything else that isn't a full sha, are rejected
+    for rev in [
+        "855f331",
+        &SHA[..39],
+        &format!("{SHA}0"),
+        &SHA.replace('8', "g"),
+    ] {
+        assert!(
+            format!("git/github/dtolnay/syn/{rev}")
+                .parse::<Coordinate>()
+                .is_err(),
+            "{rev}"
+        );
+        assert!(Coordinate::github("dtolnay", "syn", rev).is_err(), "{rev}");
+    }
+
+    // Commit shas are only parsed for the git shape
+    let sha_like = CoordVersion::for_shape(&Shape::Crate, SHA);
+    assert_eq!(CoordVersion::Any(SHA.to_owned()), sha_like);
+    assert_eq!(None, sha_like.as_sha());
+    assert_eq!(SHA, sha_like.short());
+    assert!(sha_like.matches(&syn.version));
+
+    let builder = Coordinate::builder(Shape::Git, Provider::Github)
+        .namespace("dtolnay")
+        .name("syn")
+        .version(SHA.to_uppercase().as_str())
+        .build()
+        .unwrap();
+    assert_eq!(syn, builder);
+}
+
+#[test]
+fn parse_errors() {
+    use cd::{CoordinateParseError as Cpe, Error};
+
+    let invalid = |s: &str| Cpe::InvalidEncoding {
+        segment: "name",
+        value: s.to_owned(),
+    };
+
+    let cases = [
+        ("", Cpe::EmptySegment("shape")),
+        ("crate", Cpe::MissingSegment("provider")),
+        ("crate/cratesio", Cpe::MissingSegment("namespace")),
+        ("npm/npmjs/-", Cpe::MissingSegment("name")),
+        ("crate/cratesio/-/syn", Cpe::MissingSegment("version")),
+        ("crate/cratesio/-/syn/", Cpe::TrailingSlash),
+        ("crate/cratesio/-/syn/1.0.14/", Cpe::TrailingSlash),
+        ("crate//-/syn/1.0.14", Cpe::EmptySegment("provider")),
+        ("crate/cratesio/-//1.0.14", Cpe::EmptySegment("name")),
+        ("npm/npmjs//node/18.11.9", Cpe::EmptySegment("namespace")),
+        (
+            "hex/cratesio/-/syn/1.0.14",
+            Cpe::UnknownShape("hex".to_owned()),
+        ),
+        (
+            "crate/hexpm/-/syn/1.0.14",
+            Cpe::UnknownProvider("hexpm".to_owned()),
+        ),
+        (
+            "crate/cratesio/-/syn/1.0.14/extra",
+            Cpe::TrailingSegment("extra".to_owned()),
+        ),
+        (
+            "crate/cratesio/-/syn/1.0.14/pr",
+            Cpe::MissingSegment("curation PR number"),
+        ),
+        (
+            "crate/cratesio/-/syn/1.0.14/pr/-1",
+            Cpe::InvalidPr("-1".to_owned()),
+        ),
+        (
+            "crate/cratesio/-/syn/1.0.14/pr/2/extra",
+            Cpe::TrailingSegment("extra".to_owned()),
+        ),
+        ("crate/cratesio/-/%ff/1.0.14", invalid("%ff")),
+        ("go/golang/github.com/!1/1.0.0", invalid("!1")),
+    ];
+
+    for (input, expected) in cases {
+        match input.parse::<Coordinate>() {
+            Err(Error::ParseCoordinate { input: i, source }) => {
+                assert_eq!(input, i);
+                assert_eq!(expected, source, "{input}");
+            }
+            other => panic!("unexpected result for '{input}': {other:?}"),
+        }
+    }
+
+    // The input is included in the error message
+    assert_eq!(
+        "invalid coordinate 'crate/cratesio/-/syn/1.0.14/': trailing '/'",
+        "crate/cratesio/-/syn/1.0.14/"
+            .parse::<Coordinate>()
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn coordinate_set() {
+    use cd::CoordinateSet;
+
+    let coord = |s: &str| -> Coordinate { s.parse().unwrap() };
+
+    let mut set: CoordinateSet = [
+        "crate/cratesio/-/syn/1.0.14",
+        "npm/npmjs/@types/node/18.11.9",
+        "crate/cratesio/-/serde/1.0.190",
+        "crate/cratesio/-/syn/1.0.14",
+    ]
+    .into_iter()
+    .map(coord)
+    .collect();
+    assert_eq!(3, set.len());
+
+    // Coordinates that only differ by their curation PR are the same
+    // component, and the first one inserted is kept
+    assert!(!set.insert(coord("crate/cratesio/-/syn/1.0.14/pr/2")));
+    assert!(set.contains(&coord("crate/cratesio/-/syn/1.0.14/pr/3")));
+    assert!(set.iter().all(|c| c.curation_pr.is_none()));
+
+    set.extend([
+        coord("crate/cratesio/-/syn/2.0.38/pr/5"),
+        coord("crate/cratesio/-/syn/2.0.38"),
+        coord("crate/cratesio/-/anyhow/1.0.75"),
+    ]);
+    assert_eq!(5, set.len());
+    assert!(!set.contains(&coord("crate/cratesio/-/syn/2.0.39")));
+
+    let expected = [
+        "crate/cratesio/-/anyhow/1.0.75",
+        "crate/cratesio/-/serde/1.0.190",
+        "crate/cratesio/-/syn/1.0.14",
+        "crate/cratesio/-/syn/2.0.38/pr/5",
+        "npm/npmjs/@types/node/18.11.9",
+    ];
+    assert_eq!(
+        expected.as_slice(),
+        set.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+    );
+
+    // Chunks are the same regardless of the order coordinates were inserted
+    let reversed: CoordinateSet = expected.iter().rev().map(|s| coord(s)).collect();
+    let chunks = |set: &CoordinateSet| {
+        set.chunks(2)
+            .map(|chunk| chunk.iter().map(|c| c.to_string()).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(chunks(&set), chunks(&reversed));
+    assert_eq!(
+        vec![2, 2, 1],
+        set.chunks(2).map(|chunk| chunk.len()).collect::<Vec<_>>()
+    );
+
+    // The set can be used directly when creating requests
+    let requests: Vec<_> = cd::definitions::get(2, set.iter().cloned()).collect();
+    assert_eq!(3, requests.len());
+    assert_eq!(
+        r#"["crate/cratesio/-/anyhow/1.0.75","crate/cratesio/-/serde/1.0.190"]"#,
+        std::str::from_utf8(requests[0].body()).unwrap()
+    );
+
+    // Curation PRs aren't serialized, the same as coordinates
+    let json = serde_json::to_string(&set).unwrap();
+    assert!(json.starts_with(r#"["crate/cratesio/-/anyhow/1.0.75","#));
+    assert!(!json.contains("/pr/"));
+    let reloaded: CoordinateSet = serde_json::from_str(&json).unwrap();
+    assert_eq!(set, reloaded);
+    assert_eq!(set.into_iter().count(), reloaded.len());
+}
@@ -0,0 +1,30 @@
+use cd::debug::to_curl;
+
+#[test]
+fn batch_as_curl() {
+    let coords = vec!["crate/cratesio/-/syn/1.0.14".parse().unwrap()];
+    let mut req = cd::definitions::get(10, coords).next().unwrap();
+    req.headers_mut().insert(
+        http::header::AUTHORIZATION,
+        http::HeaderValue::from_static("Bearer hunter2"),
+    );
+
+    assert_eq!(
+        "curl -X POST 'https://api.clearlydefined.io/definitions' -H 'content-type: application/json' -H 'accept: application/json' -H 'authorization: <redacted>' -d '[\"crate/cratesio/-/syn/1.0.14\"]'",
+        to_curl(&req)
+    );
+}
+
+#[test]
+fn quotes_arguments() {
+    let mut req = cd::definitions::search("syn");
+    req.headers_mut().insert(
+        http::header::USER_AGENT,
+        http::HeaderValue::from_static("it's-me/1.0"),
+    );
+
+    assert_eq!(
+        r"curl -X GET 'https://api.clearlydefined.io/definitions?pattern=syn' -H 'accept: application/json' -H 'user-agent: it'\''s-me/1.0'",
+        to_curl(&req)
+    );
+}
@@ -1,7 +1,14 @@
 use cd::definitions as defs;
+use serde::Deserialize;
 use std::convert::TryFrom;
 
 const GET_DATA: &str = include_str!("data/definitions-get.json");
+const MAVEN_DATA: &str = include_str!("data/definitions-maven.json");
+const MAVEN_GOOGLE_DATA: &str = include_str!("data/definitions-mavengoogle.json");
+const RUBYGEMS_DATA: &str = include_str!("data/definitions-rubygems.json");
+const DEBIAN_DATA: &str = include_str!("data/definitions-debian.json");
+const DEBSRC_DATA: &str = include_str!("data/definitions-debsrc.json");
+const NPM_DATA: &str = include_str!("data/definitions-npm.json");
 //const SYN_ONLY: &str = include_str!("data/syn-only.json");
 
 #[test]
@@ -31,6 +38,12 @@ fn deserialize_get_response() {
             .find(|d| d.coordinates.name == "syn")
             .unwrap();
 
+        {
+            let meta = syn.meta.as_ref().unwrap();
+            assert_eq!("1.6.1", meta.schema_version);
+            assert_eq!("2020-01-23T00:25:22.433Z", meta.updated);
+        }
+
         {
             let desc = syn.described.as_ref().unwrap();
             assert_eq!(
@@ -69,11 +82,12 @@ fn deserialize_get_response() {
             );
             assert_eq!(
                 defs::Hashes {
-                    sha1: "85b0fe2790310f9d6daf04393bc0cf266841d861".to_owned(),
+                    sha1: Some("85b0fe2790310f9d6daf04393bc0cf266841d861".to_owned()),
                     sha256: Some(
                         "af6f3550d8dff9ef7dc34d384ac6f107e5d31c8f57d9f28e0081503f547ac8f5"
                             .to_owned()
                     ),
+                    ..Default::default()
                 },
                 desc.hashes
             );
@@ -147,11 +161,12 @@ fn deserialize_get_response() {
                 let build = files.iter().find(|f| f.path == "build.rs").unwrap();
                 assert_eq!(
                     Some(defs::Hashes {
-                        sha1: "e58729c91f5fa640cdc10944579d803c47071451".to_owned(),
+                        sha1: Some("e58729c91f5fa640cdc10944579d803c47071451".to_owned()),
                         sha256: Some(
                             "2570006136c4fed9199b9c23c100a99e1be04d6c6a3e9630a6613a67baedf503"
                                 .to_owned()
                         ),
+                        ..Default::default()
                     }),
                     build.hashes
                 );
@@ -181,3 +196,1669 @@ fn deserialize_get_response() {
         assert_eq!(["license".to_owned()], &lic.natures[..]);
     }
 }
+
+#[test]
+fn deserialize_maven() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(MAVEN_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(1, definitions.len());
+
+    let def = &definitions[0];
+    assert_eq!(cd::Shape::Maven, def.coordinates.shape);
+    assert_eq!(cd::Provider::MavenCentral, def.coordinates.provider);
+    assert_eq!(
+        Some("org.apache.commons"),
+        def.coordinates.namespace.as_deref()
+    );
+    assert_eq!("commons-lang3", def.coordinates.name);
+    assert_eq!(92, def.scores.effective);
+
+    let desc = def.described.as_ref().unwrap();
+    assert_eq!("2021-02-26", desc.release_date.to_string());
+    let source = desc.source_location.as_ref().unwrap();
+    assert_eq!("sourcearchive", source.r#type);
+    assert_eq!("org.apache.commons", source.namespace);
+    assert_eq!(268, desc.files);
+
+    let licensed = def.licensed.as_ref().unwrap();
+    assert_eq!("Apache-2.0", licensed.declared);
+    assert_eq!(
+        ["Apache-2.0"],
+        licensed.facets.core.discovered.expressions.as_slice()
+    );
+
+    assert_eq!(3, def.files.len());
+    assert_eq!(["license"], def.files[0].natures.as_slice());
+}
+
+#[test]
+fn deserialize_maven_google() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(MAVEN_GOOGLE_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(1, definitions.len());
+
+    let def = &definitions[0];
+    assert_eq!(cd::Shape::Maven, def.coordinates.shape);
+    assert_eq!(cd::Provider::MavenGoogle, def.coordinates.provider);
+    assert_eq!("core", def.coordinates.name);
+    assert!(def.is_harvested());
+    assert_eq!("Apache-2.0", def.licensed.as_ref().unwrap().declared);
+
+    let coord: cd::Coordinate = "maven/mavengoogle/androidx.core/core/1.9.0"
+        .parse()
+        .unwrap();
+    assert_eq!(cd::Provider::MavenGoogle, coord.provider);
+    assert_eq!(
+        "maven/mavengoogle/androidx.core/core/1.9.0",
+        coord.to_string()
+    );
+}
+
+#[test]
+fn dedup_best() {
+    let data: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+    let syn = &data["crate/cratesio/-/syn/1.0.14"];
+    let tokio = defs::Definition::deserialize(&data["crate/cratesio/-/tokio/0.1.15"]).unwrap();
+
+    let with_score = |score: u8, files: usize| {
+        let mut def = syn.clone();
+        def["scores"]["effective"] = score.into();
+        def["files"] = serde_json::Value::Array(def["files"].as_array().unwrap()[..files].to_vec());
+        defs::Definition::deserialize(&def).unwrap()
+    };
+
+    let deduped = defs::dedup_best(vec![
+        with_score(50, 10),
+        tokio,
+        with_score(87, 5),
+        with_score(87, 10),
+        with_score(87, 2),
+    ]);
+
+    assert_eq!(2, deduped.len());
+    assert_eq!("syn", deduped[0].coordinates.name);
+    assert_eq!(87, deduped[0].scores.effective);
+    assert_eq!(10, deduped[0].files.len());
+    assert_eq!("tokio", deduped[1].coordinates.name);
+}
+
+#[test]
+fn deserialize_rubygems() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(RUBYGEMS_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(1, definitions.len());
+
+    let def = &definitions[0];
+    assert_eq!(cd::Shape::Gem, def.coordinates.shape);
+    assert_eq!(cd::Provider::RubyGems, def.coordinates.provider);
+    assert_eq!("rails", def.coordinates.name);
+    assert_eq!("gem/rubygems/-/rails/7.0.4", def.coordinates.to_string());
+
+    let desc = def.described.as_ref().unwrap();
+    assert_eq!(
+        Some("https://rubyonrails.org"),
+        desc.project_website.as_deref()
+    );
+    let source = desc.source_location.as_ref().unwrap();
+    assert_eq!("git", source.r#type);
+    assert_eq!("github", source.provider);
+    assert_eq!("rails", source.namespace);
+    assert_eq!(Some(true), def.source_matches_package());
+
+    let licensed = def.licensed.as_ref().unwrap();
+    assert_eq!("MIT", licensed.declared);
+    assert!(licensed.facets.core.discovered.expressions.is_empty());
+    assert!(licensed.facets.core.attribution.parties.is_empty());
+
+    let coord: cd::Coordinate = "gem/rubygems/-/rails/7.0.4".parse().unwrap();
+    assert_eq!("gem/rubygems/-/rails/7.0.4", coord.to_string());
+}
+
+#[test]
+fn deserialize_debian() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(DEBIAN_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(1, definitions.len());
+
+    let def = &definitions[0];
+    assert_eq!(cd::Shape::Deb, def.coordinates.shape);
+    assert_eq!(cd::Provider::Debian, def.coordinates.provider);
+    assert_eq!("zlib1g", def.coordinates.name);
+    assert_eq!(
+        cd::CoordVersion::Any("1:1.2.11.dfsg-2_amd64".to_owned()),
+        def.coordinates.revision
+    );
+
+    // Debian source locations are source packages with no namespace
+    let desc = def.described.as_ref().unwrap();
+    let source = desc.source_location.as_ref().unwrap();
+    assert_eq!("debsrc", source.r#type);
+    assert_eq!("debian", source.provider);
+    assert!(source.namespace.is_empty());
+    assert_eq!("1:1.2.11.dfsg-2", source.revision);
+    assert_eq!(4, desc.files);
+
+    assert_eq!(
+        "debsrc/debian/-/zlib/1:1.2.11.dfsg-2",
+        def.source_coordinate().unwrap().to_string()
+    );
+
+    let licensed = def.licensed.as_ref().unwrap();
+    assert_eq!("Zlib", licensed.declared);
+    assert_eq!(
+        ["usr/share/doc/zlib1g/copyright"],
+        def.files
+            .iter()
+            .filter(|file| file.natures.iter().any(|n| n == "license"))
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>()
+            .as_slice()
+    );
+}
+
+#[test]
+fn deserialize_file_natures() {
+    let array: defs::File =
+        serde_json::from_str(r#"{ "path": "LICENSE", "natures": ["license", "notice"] }"#).unwrap();
+    let csv: defs::File =
+        serde_json::from_str(r#"{ "path": "LICENSE", "natures": "license, notice" }"#).unwrap();
+
+    assert_eq!(
+        ["license".to_owned(), "notice".to_owned()],
+        &array.natures[..]
+    );
+    assert_eq!(array.natures, csv.natures);
+}
+
+#[test]
+fn search_revisions() {
+    let resp = http::Response::builder()
+        .status(200)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(
+            r#"[
+                "crate/cratesio/-/syn/1.0.14",
+                "crate/cratesio/-/syn-mid/0.5.0",
+                "crate/cratesio/-/syn/0.15.44",
+                "crate/cratesio/-/syn/1.0.2",
+                "crate/cratesio/-/syn-mid/0.4.0"
+            ]"#,
+        )
+        .unwrap();
+
+    let search = defs::SearchResponse::try_from(resp).unwrap();
+    assert_eq!(5, search.coordinates.len());
+
+    let semver = |vs: &str| cd::CoordVersion::Semver(vs.parse().unwrap());
+
+    assert_eq!(
+        vec![semver("0.15.44"), semver("1.0.2"), semver("1.0.14")],
+        search.revisions_for("syn")
+    );
+    assert_eq!(
+        vec![semver("0.4.0"), semver("0.5.0")],
+        search.revisions_for("syn-mid")
+    );
+    assert!(search.revisions_for("quote").is_empty());
+}
+
+#[test]
+fn deserialize_get_response_array() {
+    fn parse(body: String) -> Vec<defs::Definition> {
+        let resp = http::Response::builder().status(200).body(body).unwrap();
+        defs::GetResponse::try_from(resp).unwrap().definitions
+    }
+
+    let map: serde_json::Map<String, serde_json::Value> = serde_json::from_str(GET_DATA).unwrap();
+    let array = serde_json::to_string(&map.values().collect::<Vec<_>>()).unwrap();
+
+    let from_map = parse(GET_DATA.to_owned());
+    let from_array = parse(format!("  \n{}", array));
+
+    assert_eq!(3, from_array.len());
+    for (m, a) in from_map.iter().zip(from_array.iter()) {
+        assert_eq!(m.coordinates.to_string(), a.coordinates.to_string());
+        assert_eq!(m.described.is_some(), a.described.is_some());
+        assert_eq!(m.files.len(), a.files.len());
+        assert_eq!(m.scores.effective, a.scores.effective);
+    }
+}
+
+#[test]
+fn missing_coordinates() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+
+    let get = defs::GetResponse::try_from(resp).unwrap();
+
+    let requested: Vec<cd::Coordinate> = [
+        "crate/cratesio/-/syn/1.0.14",
+        "crate/cratesio/-/tame-gcs/0.4.0",
+        "crate/cratesio/-/tokio/0.1.15",
+        "crate/cratesio/-/quote/1.0.2",
+    ]
+    .iter()
+    .map(|c| c.parse().unwrap())
+    .collect();
+
+    let missing: Vec<_> = get
+        .missing(&requested)
+        .into_iter()
+        .map(|c| c.to_string())
+        .collect();
+
+    assert_eq!(
+        vec![
+            "crate/cratesio/-/tame-gcs/0.4.0".to_owned(),
+            "crate/cratesio/-/quote/1.0.2".to_owned()
+        ],
+        missing
+    );
+}
+
+#[test]
+fn get_includes_curation_prs() {
+    let coords = [
+        "crate/cratesio/-/syn/1.0.14/pr/123",
+        "crate/cratesio/-/quote/1.0.2",
+    ]
+    .iter()
+    .map(|c| c.parse::<cd::Coordinate>().unwrap());
+
+    let reqs: Vec<_> = defs::get(10, coords).collect();
+    assert_eq!(1, reqs.len());
+
+    let body: Vec<String> = serde_json::from_slice(reqs[0].body()).unwrap();
+    assert_eq!(
+        vec![
+            "crate/cratesio/-/syn/1.0.14/pr/123".to_owned(),
+            "crate/cratesio/-/quote/1.0.2".to_owned(),
+        ],
+        body
+    );
+}
+
+#[test]
+fn get_encodes_coordinates() {
+    let coords: Vec<cd::Coordinate> = [
+        "go/golang/github.com%2fstretchr/testify/v1.8.0",
+        "npm/npmjs/@types/node/14.0.0",
+    ]
+    .iter()
+    .map(|c| c.parse().unwrap())
+    .collect();
+
+    // The body uses the same, encoded, form as displaying the coordinates
+    let reqs: Vec<_> = defs::get(10, coords.clone()).collect();
+    let body: Vec<String> = serde_json::from_slice(reqs[0].body()).unwrap();
+    assert_eq!(
+        coords.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+        body
+    );
+    assert_eq!("go/golang/github.com%2fstretchr/testify/v1.8.0", body[0]);
+}
+
+#[test]
+fn licenses_by_directory() {
+    let def: defs::Definition = serde_json::from_str(
+        r#"{
+            "coordinates": { "type": "crate", "provider": "cratesio", "name": "vendored", "revision": "0.1.0" },
+            "described": null,
+            "licensed": null,
+            "files": [
+                { "path": "LICENSE", "license": "MIT" },
+                { "path": "src/lib.rs", "license": "MIT" },
+                { "path": "src/util/mod.rs", "license": "Apache-2.0" },
+                { "path": "src/no_license.rs" },
+                { "path": "vendor/zlib/inflate.c", "license": "Zlib" }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let set = |l: &[&str]| {
+        l.iter()
+            .map(|s| (*s).to_owned())
+            .collect::<std::collections::BTreeSet<_>>()
+    };
+
+    let by_dir = def.licenses_by_directory(1);
+    assert_eq!(3, by_dir.len());
+    assert_eq!(set(&["MIT"]), by_dir[""]);
+    assert_eq!(set(&["Apache-2.0", "MIT"]), by_dir["src"]);
+    assert_eq!(set(&["Zlib"]), by_dir["vendor"]);
+
+    let by_dir = def.licenses_by_directory(2);
+    assert_eq!(set(&["MIT"]), by_dir["src"]);
+    assert_eq!(set(&["Apache-2.0"]), by_dir["src/util"]);
+    assert_eq!(set(&["Zlib"]), by_dir["vendor/zlib"]);
+}
+
+#[cfg(feature = "spdx")]
+fn license(declared: &str) -> defs::License {
+    let score = r#"{ "total": 0, "declared": 0, "discovered": 0, "consistency": 0, "spdx": 0, "texts": 0 }"#;
+
+    serde_json::from_str(&format!(
+        r#"{{
+            "declared": "{}",
+            "facets": {{ "core": {{
+                "attribution": {{ "unknown": 0 }},
+                "discovered": {{ "unknown": 0, "expressions": [] }},
+                "files": 0
+            }} }},
+            "toolScore": {},
+            "score": {}
+        }}"#,
+        declared, score, score
+    ))
+    .unwrap()
+}
+
+#[cfg(feature = "spdx")]
+#[test]
+fn most_restrictive() {
+    let ranking = [
+        "AGPL-3.0-only",
+        "GPL-3.0-only",
+        "MPL-2.0",
+        "Apache-2.0",
+        "MIT",
+    ];
+
+    assert_eq!(
+        Some("GPL-3.0-only".to_owned()),
+        license("MIT OR GPL-3.0-only").most_restrictive(&ranking)
+    );
+    assert_eq!(
+        Some("Apache-2.0".to_owned()),
+        license("Apache-2.0 AND MIT").most_restrictive(&ranking)
+    );
+    // Licenses that aren't in the ranking are ignored
+    assert_eq!(
+        Some("MIT".to_owned()),
+        license("MIT OR Zlib").most_restrictive(&ranking)
+    );
+    assert_eq!(None, license("Zlib").most_restrictive(&ranking));
+    assert_eq!(None, license("NOASSERTION").most_restrictive(&ranking));
+}
+
+#[test]
+fn license_report_row() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+
+    let rows: Vec<_> = definitions
+        .iter()
+        .map(defs::LicenseReportRow::from)
+        .collect();
+
+    let syn = rows
+        .iter()
+        .find(|r| r.coordinate == "crate/cratesio/-/syn/1.0.14")
+        .unwrap();
+    assert_eq!(
+        &defs::LicenseReportRow {
+            coordinate: "crate/cratesio/-/syn/1.0.14".to_owned(),
+            declared_license: Some("Apache-2.0 AND MIT".to_owned()),
+            effective_score: 87,
+            release_date: Some("2020-01-20".to_owned()),
+            source_url: Some(
+                "https://github.com/dtolnay/syn/tree/855f331cf0e14916a1c3026786b59e6f6b6f2d6f"
+                    .to_owned()
+            ),
+        },
+        syn
+    );
+
+    let tame_gcs = rows
+        .iter()
+        .find(|r| r.coordinate == "crate/cratesio/-/tame-gcs/0.4.0")
+        .unwrap();
+    assert!(tame_gcs.declared_license.is_none());
+    assert!(tame_gcs.release_date.is_none());
+    assert!(tame_gcs.source_url.is_none());
+}
+
+#[test]
+fn description_without_urls() {
+    let data: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+    let described = &data["crate/cratesio/-/syn/1.0.14"]["described"];
+
+    let mut missing = described.clone();
+    missing.as_object_mut().unwrap().remove("urls");
+    let desc = defs::Description::deserialize(&missing).unwrap();
+    assert!(desc.urls.is_empty());
+
+    let mut null = described.clone();
+    null["urls"] = serde_json::Value::Null;
+    let desc = defs::Description::deserialize(&null).unwrap();
+    assert!(desc.urls.is_empty());
+}
+
+#[test]
+fn deprecated() {
+    let data: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+    let syn = &data["crate/cratesio/-/syn/1.0.14"];
+
+    let def = defs::Definition::deserialize(syn).unwrap();
+    assert_eq!(None, def.described.as_ref().unwrap().deprecated);
+    assert!(!def.is_deprecated());
+
+    let mut deprecated = syn.clone();
+    deprecated["described"]["deprecated"] = serde_json::Value::Bool(true);
+    let def = defs::Definition::deserialize(&deprecated).unwrap();
+    assert_eq!(Some(true), def.described.as_ref().unwrap().deprecated);
+    assert!(def.is_deprecated());
+}
+
+#[test]
+fn coordinate_diff() {
+    let coords =
+        |c: &[&str]| -> Vec<cd::Coordinate> { c.iter().map(|c| c.parse().unwrap()).collect() };
+
+    let old = coords(&[
+        "crate/cratesio/-/syn/1.0.14",
+        "crate/cratesio/-/quote/1.0.2",
+        "crate/cratesio/-/proc-macro-hack/0.5.19",
+    ]);
+    let new = coords(&[
+        "crate/cratesio/-/syn/2.0.0",
+        "crate/cratesio/-/quote/1.0.2",
+        "crate/cratesio/-/unicode-ident/1.0.12",
+    ]);
+
+    let diff = defs::coordinate_diff(&old, &new);
+
+    assert_eq!(
+        coords(&["crate/cratesio/-/unicode-ident/1.0.12"]),
+        diff.added
+    );
+    assert_eq!(
+        coords(&["crate/cratesio/-/proc-macro-hack/0.5.19"]),
+        diff.removed
+    );
+    assert_eq!(vec![(old[0].clone(), new[0].clone())], diff.changed);
+}
+
+#[cfg(feature = "spdx")]
+#[test]
+fn is_copyleft() {
+    assert!(!license("MIT").is_copyleft());
+    assert!(!license("Apache-2.0 AND MIT").is_copyleft());
+    assert!(license("GPL-3.0-only").is_copyleft());
+    assert!(license("MIT AND (Apache-2.0 OR LGPL-2.1-or-later)").is_copyleft());
+    assert!(license("MPL-2.0").is_copyleft());
+}
+
+#[cfg(feature = "spdx")]
+#[test]
+fn normalized_declared() {
+    assert_eq!("GPL-2.0-only", license("GPL-2.0").normalized_declared());
+    assert_eq!(
+        "MIT OR GPL-2.0-or-later",
+        license("MIT OR GPL-2.0+").normalized_declared()
+    );
+    assert_eq!(
+        "LGPL-2.1-only WITH Classpath-exception-2.0 AND (MIT OR GPL-3.0-only)",
+        license("LGPL-2.1 WITH Classpath-exception-2.0 AND (MIT OR GPL-3.0)").normalized_declared()
+    );
+    // Current identifiers are left alone
+    assert_eq!(
+        "GPL-3.0-or-later AND Apache-2.0",
+        license("GPL-3.0-or-later AND Apache-2.0").normalized_declared()
+    );
+}
+
+#[test]
+fn conservative_license() {
+    let data: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+    let with_licenses = |declared: &str, discovered: &[&str]| {
+        let mut syn = data["crate/cratesio/-/syn/1.0.14"].clone();
+        syn["licensed"]["declared"] = declared.into();
+        syn["licensed"]["facets"]["core"]["discovered"]["expressions"] = discovered.into();
+        defs::Definition::deserialize(&syn).unwrap()
+    };
+
+    let syn = defs::Definition::deserialize(&data["crate/cratesio/-/syn/1.0.14"]).unwrap();
+    assert_eq!(
+        Some("Apache-2.0 AND MIT"),
+        syn.conservative_license().as_deref()
+    );
+
+    assert_eq!(
+        Some("MIT AND Apache-2.0 AND BSD-3-Clause"),
+        with_licenses("MIT", &["MIT", "Apache-2.0", "BSD-3-Clause"])
+            .conservative_license()
+            .as_deref()
+    );
+    assert_eq!(
+        Some("(MIT OR Apache-2.0) AND Unicode-DFS-2016"),
+        with_licenses("MIT OR Apache-2.0", &["NOASSERTION", "Unicode-DFS-2016"])
+            .conservative_license()
+            .as_deref()
+    );
+    assert_eq!(
+        None,
+        with_licenses("NOASSERTION", &[]).conservative_license()
+    );
+
+    let tame = defs::Definition::deserialize(&data["crate/cratesio/-/tame-gcs/0.4.0"]).unwrap();
+    assert_eq!(None, tame.conservative_license());
+}
+
+#[test]
+fn validate_batch() {
+    let coords: Vec<cd::Coordinate> = (0..=defs::MAX_BATCH_SIZE as u64)
+        .map(|patch| cd::Coordinate {
+            shape: cd::Shape::Crate,
+            provider: cd::Provider::CratesIo,
+            namespace: None,
+            name: "syn".to_owned(),
+            version: cd::CoordVersion::Semver(semver::Version::new(1, 0, patch)),
+            curation_pr: None,
+        })
+        .collect();
+
+    assert!(defs::validate_batch(&coords[..1000]).is_ok());
+    assert!(defs::validate_batch(&coords[..1]).is_ok());
+
+    let err = defs::validate_batch(&coords).unwrap_err();
+    assert!(err.to_string().contains("1001"));
+}
+
+#[test]
+fn file_hashes() {
+    let file: defs::File = serde_json::from_value(serde_json::json!({
+        "path": "src/lib.rs",
+        "hashes": {
+            "md5": "d41d8cd98f00b204e9800998ecf8427e",
+            "sha512": "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        },
+    }))
+    .unwrap();
+
+    assert_eq!(
+        Some(defs::Hashes {
+            md5: Some("d41d8cd98f00b204e9800998ecf8427e".to_owned()),
+            sha512: Some("cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e".to_owned()),
+            ..Default::default()
+        }),
+        file.hashes
+    );
+}
+
+#[cfg(feature = "spdx")]
+#[test]
+fn evaluate() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    let mut harvested = defs::GetResponse::try_from(
+        http::Response::builder()
+            .status(200)
+            .body(GET_DATA)
+            .unwrap(),
+    )
+    .unwrap()
+    .definitions;
+    harvested.retain(|def| def.is_harvested());
+
+    let syn = || "crate/cratesio/-/syn/1.0.14".to_owned();
+    let tame = || "crate/cratesio/-/tame-gcs/0.4.0".to_owned();
+
+    // The default policy allows everything
+    assert!(defs::evaluate(&definitions, &defs::Policy::default()).passed());
+
+    // syn is Apache-2.0 AND MIT, tokio is MIT
+    let verdict = defs::evaluate(
+        &definitions,
+        &defs::Policy {
+            allowed_licenses: Some("MIT".to_owned()),
+            ..Default::default()
+        },
+    );
+    assert!(!verdict.passed());
+    assert_eq!(2, verdict.violations.len());
+    assert_eq!(
+        vec![defs::Violation::License("Apache-2.0 AND MIT".to_owned())],
+        verdict.violations[&syn()]
+    );
+    assert_eq!(
+        vec![defs::Violation::NotHarvested],
+        verdict.violations[&tame()]
+    );
+
+    let verdict = defs::evaluate(
+        &harvested,
+        &defs::Policy {
+            allowed_licenses: Some("MIT OR Apache-2.0".to_owned()),
+            min_score: 80,
+            ..Default::default()
+        },
+    );
+    assert_eq!(1, verdict.violations.len());
+    assert!(matches!(
+        verdict.violations["crate/cratesio/-/tokio/0.1.15"].as_slice(),
+        [defs::Violation::Score(52)]
+    ));
+
+    // Everything in the fixture is old
+    let verdict = defs::evaluate(
+        &definitions,
+        &defs::Policy {
+            max_age_days: Some(365),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(
+        verdict.violations[&syn()].as_slice(),
+        [defs::Violation::Age(age)] if *age > 365
+    ));
+    let verdict = defs::evaluate(
+        &harvested,
+        &defs::Policy {
+            max_age_days: Some(100_000),
+            ..Default::default()
+        },
+    );
+    assert!(verdict.passed());
+}
+
+#[test]
+fn get_options() {
+    let coords = (0..5).map(|patch| cd::Coordinate {
+        shape: cd::Shape::Crate,
+        provider: cd::Provider::CratesIo,
+        namespace: None,
+        name: "syn".to_owned(),
+        version: cd::CoordVersion::Semver(semver::Version::new(1, 0, patch)),
+        curation_pr: None,
+    });
+
+    let options = defs::GetOptions::default()
+        .chunk_size(2)
+        .base_uri("http://localhost:4000/")
+        .user_agent(http::HeaderValue::from_static("cd-test/0.1.0"));
+
+    let requests: Vec<_> = defs::get_with(&options, coords.clone()).collect();
+    assert_eq!(3, requests.len());
+
+    for req in &requests {
+        assert_eq!("http://localhost:4000/definitions", req.uri());
+        assert_eq!(
+            "cd-test/0.1.0",
+            req.headers()[http::header::USER_AGENT].to_str().unwrap()
+        );
+    }
+
+    let requests: Vec<_> = defs::get_with(&defs::GetOptions::default(), coords).collect();
+    assert_eq!(1, requests.len());
+    assert_eq!(
+        "https://api.clearlydefined.io/definitions",
+        requests[0].uri()
+    );
+    assert!(!requests[0].headers().contains_key(http::header::USER_AGENT));
+}
+
+#[test]
+fn get_options_chunk_size_clamped() {
+    let coords = |count: u64| {
+        (0..count).map(|patch| cd::Coordinate::crates_io("syn", &semver::Version::new(1, 0, patch)))
+    };
+
+    // A chunk size of 0 would otherwise put every coordinate in one request,
+    // regardless of the API limit
+    let options = defs::GetOptions::default().chunk_size(0);
+    assert_eq!(3, defs::get_with(&options, coords(3)).count());
+
+    let options = defs::GetOptions::default().chunk_size(defs::MAX_BATCH_SIZE + 1);
+    let requests: Vec<_> =
+        defs::get_with(&options, coords(defs::MAX_BATCH_SIZE as u64 + 1)).collect();
+    assert_eq!(2, requests.len());
+    let first: Vec<String> = serde_json::from_slice(requests[0].body()).unwrap();
+    assert_eq!(defs::MAX_BATCH_SIZE, first.len());
+}
+
+#[test]
+fn strips_bom() {
+    let mut body = b"\xEF\xBB\xBF".to_vec();
+    body.extend_from_slice(GET_DATA.as_bytes());
+
+    let resp = http::Response::builder().status(200).body(body).unwrap();
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(3, definitions.len());
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(b"\xEF\xBB\xBF[\"crate/cratesio/-/syn/1.0.14\"]".to_vec())
+        .unwrap();
+    let search = defs::SearchResponse::try_from(resp).unwrap();
+    assert_eq!(1, search.coordinates.len());
+}
+
+#[test]
+fn retain_relevant_files() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+
+    let mut definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    let syn = definitions
+        .iter_mut()
+        .find(|def| def.coordinates.name == "syn")
+        .unwrap();
+
+    syn.files = serde_json::from_value(serde_json::json!([
+        { "path": "LICENSE-MIT", "natures": ["license"] },
+        { "path": "NOTICE", "natures": "notice" },
+        { "path": "src/lib.rs", "attributions": ["Copyright (c) David Tolnay"] },
+        { "path": "src/parse.rs", "license": "MIT OR Apache-2.0" },
+        { "path": "Cargo.toml" },
+    ]))
+    .unwrap();
+
+    syn.retain_relevant_files();
+
+    assert_eq!(
+        ["LICENSE-MIT", "NOTICE", "src/lib.rs"],
+        syn.files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>()
+            .as_slice()
+    );
+}
+
+#[test]
+fn partial_content() {
+    use cd::ApiResponse;
+
+    let map: serde_json::Map<String, serde_json::Value> = serde_json::from_str(GET_DATA).unwrap();
+    let partial: serde_json::Map<_, _> = map
+        .into_iter()
+        .filter(|(coord, _)| coord.contains("/syn/"))
+        .collect();
+
+    let resp = http::Response::builder()
+        .status(http::StatusCode::PARTIAL_CONTENT)
+        .body(bytes::Bytes::from(serde_json::to_vec(&partial).unwrap()))
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from_parts(resp).unwrap().definitions;
+    assert_eq!(1, definitions.len());
+    assert_eq!("syn", definitions[0].coordinates.name);
+}
+
+#[test]
+fn trailing_garbage() {
+    let mut body = GET_DATA.as_bytes().to_vec();
+    body.extend_from_slice(b"\r\n0\r\n\r\nkeep-alive junk");
+
+    let resp = http::Response::builder().status(200).body(body).unwrap();
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(3, definitions.len());
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(b"[\"crate/cratesio/-/syn/1.0.14\"]\0\0\0".to_vec())
+        .unwrap();
+    let search = defs::SearchResponse::try_from(resp).unwrap();
+    assert_eq!(1, search.coordinates.len());
+}
+
+#[test]
+fn partition() {
+    let coords = |count: u64| {
+        (0..count).map(|patch| cd::Coordinate {
+            shape: cd::Shape::Crate,
+            provider: cd::Provider::CratesIo,
+            namespace: None,
+            name: "syn".to_owned(),
+            version: cd::CoordVersion::Semver(semver::Version::new(1, 0, patch)),
+            curation_pr: None,
+        })
+    };
+
+    // 25 coordinates in chunks of 3 is 9 requests, 8 with 3 and 1 with 1
+    let buckets = defs::partition(coords(25), 3, 3);
+    assert_eq!(3, buckets.len());
+    for bucket in &buckets {
+        assert_eq!(3, bucket.len());
+    }
+
+    let count = |req: &http::Request<bytes::Bytes>| {
+        serde_json::from_slice::<Vec<String>>(req.body())
+            .unwrap()
+            .len()
+    };
+    let total: usize = buckets.iter().flatten().map(count).sum();
+    assert_eq!(25, total);
+
+    // Fewer requests than workers
+    let buckets = defs::partition(coords(2), 3, 1);
+    assert_eq!(2, buckets.len());
+    assert!(buckets.iter().all(|b| b.len() == 1));
+
+    assert!(defs::partition(coords(0), 3, 10).is_empty());
+}
+
+#[test]
+fn source_matches_package() {
+    let data: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+    let syn = &data["crate/cratesio/-/syn/1.0.14"];
+
+    let def = defs::Definition::deserialize(syn).unwrap();
+    assert_eq!(Some(true), def.source_matches_package());
+
+    let mut mismatch = syn.clone();
+    mismatch["described"]["sourceLocation"]["name"] = "totally-not-syn".into();
+    let def = defs::Definition::deserialize(&mismatch).unwrap();
+    assert_eq!(Some(false), def.source_matches_package());
+
+    let mut missing = syn.clone();
+    missing["described"]
+        .as_object_mut()
+        .unwrap()
+        .remove("sourceLocation");
+    let def = defs::Definition::deserialize(&missing).unwrap();
+    assert_eq!(None, def.source_matches_package());
+
+    let unharvested =
+        defs::Definition::deserialize(&data["crate/cratesio/-/tame-gcs/0.4.0"]).unwrap();
+    assert_eq!(None, unharvested.source_matches_package());
+}
+
+#[test]
+fn source_coordinate() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    let source = |name: &str| {
+        definitions
+            .iter()
+            .find(|def| def.coordinates.name == name)
+            .unwrap()
+            .source_coordinate()
+    };
+
+    let syn = source("syn").unwrap();
+    assert_eq!(cd::Shape::Git, syn.shape);
+    assert_eq!(cd::Provider::Github, syn.provider);
+    assert_eq!(
+        "git/github/dtolnay/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f",
+        syn.to_string()
+    );
+
+    assert_eq!(None, source("tokio"));
+    assert_eq!(None, source("tame-gcs"));
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(MAVEN_DATA)
+        .unwrap();
+
+    let def = defs::GetResponse::try_from(resp)
+        .unwrap()
+        .definitions
+        .remove(0);
+    let archive = def.source_coordinate().unwrap();
+    assert_eq!(cd::Shape::SourceArchive, archive.shape);
+    assert_eq!(
+        "sourcearchive/mavencentral/org.apache.commons/commons-lang3/3.12.0",
+        archive.to_string()
+    );
+    assert_eq!(archive, archive.to_string().parse().unwrap());
+}
+
+#[test]
+fn license_conflicts() {
+    let data: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+    let mut tokio = data["crate/cratesio/-/tokio/0.1.15"].clone();
+    tokio["files"] = serde_json::json!([
+        { "path": "LICENSE", "license": "MIT" },
+        { "path": "src/lib.rs" },
+        { "path": "src/vendored/gpl.rs", "license": "GPL-3.0-only" },
+        { "path": "src/unknown.rs", "license": "NOASSERTION" },
+    ]);
+
+    let tokio = defs::Definition::deserialize(&tokio).unwrap();
+    assert_eq!("MIT", tokio.licensed.as_ref().unwrap().declared);
+
+    let conflicts: Vec<_> = tokio
+        .license_conflicts()
+        .into_iter()
+        .map(|file| file.path.as_str())
+        .collect();
+    assert_eq!(["src/vendored/gpl.rs"], conflicts.as_slice());
+}
+
+#[test]
+fn without_source() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    let has_source = |name: &str| {
+        definitions
+            .iter()
+            .find(|def| def.coordinates.name == name)
+            .unwrap()
+            .has_source()
+    };
+
+    assert!(has_source("syn"));
+    assert!(!has_source("tokio"));
+    assert!(!has_source("tame-gcs"));
+
+    let without: Vec<_> = defs::without_source(&definitions)
+        .into_iter()
+        .map(|def| def.coordinates.name.as_str())
+        .collect();
+    assert_eq!(["tame-gcs", "tokio"], without.as_slice());
+}
+
+#[test]
+fn deserialize_debian_sources() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(DEBSRC_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(1, definitions.len());
+
+    let def = &definitions[0];
+    assert_eq!(cd::Shape::DebSrc, def.coordinates.shape);
+    assert_eq!(cd::Provider::Debian, def.coordinates.provider);
+    assert_eq!("curl", def.coordinates.name);
+    assert_eq!("7.74.0-1.3+deb11u7", def.coordinates.revision.to_string());
+    assert!(def.is_harvested());
+    assert!(!def.has_source());
+    assert_eq!("curl", def.licensed.as_ref().unwrap().declared);
+}
+
+#[test]
+fn deserialize_conda_sources() {
+    let coords: defs::DefCoords = serde_json::from_str(
+        r#"{"type":"condasrc","provider":"conda-forge","name":"numpy","revision":"1.26.4"}"#,
+    )
+    .unwrap();
+    assert_eq!(cd::Shape::CondaSrc, coords.shape);
+    assert_eq!(cd::Provider::CondaForge, coords.provider);
+    assert_eq!("condasrc/conda-forge/-/numpy/1.26.4", coords.to_string());
+
+    let source: defs::SourceLocation = serde_json::from_value(serde_json::json!({
+        "type": "condasrc",
+        "provider": "conda-forge",
+        "name": "numpy",
+        "revision": "1.26.4",
+        "url": "https://conda.anaconda.org/conda-forge/numpy-1.26.4.tar.gz",
+    }))
+    .unwrap();
+    let coord = source.to_coordinate().unwrap();
+    assert_eq!(coords.to_string(), coord.to_string());
+    assert_eq!(coord, coord.to_string().parse().unwrap());
+}
+
+#[test]
+fn deserialize_unknown_shapes() {
+    let mut body: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+    body["hex/hexpm/-/phoenix/1.7.0"] = serde_json::json!({
+        "coordinates": { "type": "hex", "provider": "hexpm", "name": "phoenix", "revision": "1.7.0" },
+        "described": null,
+        "licensed": null,
+    });
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(serde_json::to_vec(&body).unwrap())
+        .unwrap();
+
+    // An unknown shape or provider doesn't fail the rest of the response
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(4, definitions.len());
+
+    let phoenix = definitions
+        .iter()
+        .find(|def| def.coordinates.name == "phoenix")
+        .unwrap();
+    assert_eq!(
+        cd::Shape::Other("hex".to_owned()),
+        phoenix.coordinates.shape
+    );
+    assert_eq!(
+        cd::Provider::Other("hexpm".to_owned()),
+        phoenix.coordinates.provider
+    );
+    assert_eq!("hex/hexpm/-/phoenix/1.7.0", phoenix.coordinates.to_string());
+
+    // Known values are still strongly typed
+    let shape: cd::Shape = serde_json::from_value(serde_json::json!("crate")).unwrap();
+    assert_eq!(cd::Shape::Crate, shape);
+}
+
+#[test]
+fn ecosystem_stats() {
+    let mut definitions = Vec::new();
+    for data in [GET_DATA, MAVEN_DATA, MAVEN_GOOGLE_DATA, RUBYGEMS_DATA] {
+        let resp = http::Response::builder().status(200).body(data).unwrap();
+        definitions.extend(defs::GetResponse::try_from(resp).unwrap().definitions);
+    }
+
+    let stats = defs::ecosystem_stats(&definitions);
+    assert_eq!(4, stats.len());
+
+    // syn (87), tokio (52), and the unharvested tame-gcs (0)
+    let crates = &stats[&(cd::Shape::Crate, cd::Provider::CratesIo)];
+    assert_eq!(3, crates.count);
+    assert_eq!(2, crates.harvested);
+    assert!((crates.mean_score - 139.0 / 3.0).abs() < f32::EPSILON);
+
+    assert_eq!(
+        defs::EcosystemStats {
+            count: 1,
+            harvested: 1,
+            mean_score: 92.0,
+        },
+        stats[&(cd::Shape::Maven, cd::Provider::MavenCentral)]
+    );
+    assert_eq!(
+        1,
+        stats[&(cd::Shape::Maven, cd::Provider::MavenGoogle)].count
+    );
+    assert_eq!(1, stats[&(cd::Shape::Gem, cd::Provider::RubyGems)].count);
+}
+
+#[test]
+fn tools_used() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    let tools = defs::tools_used(&definitions);
+
+    assert_eq!(
+        [
+            ("clearlydefined", 2),
+            ("fossology", 1),
+            ("licensee", 2),
+            ("scancode", 2)
+        ]
+        .iter()
+        .map(|(k, v)| (String::from(*k), *v))
+        .collect::<std::collections::BTreeMap<_, _>>(),
+        tools
+    );
+}
+
+#[test]
+fn tool_version_older_than() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    let syn = definitions
+        .iter()
+        .find(|def| def.coordinates.name == "syn")
+        .unwrap();
+
+    assert_eq!(Some(true), syn.tool_version_older_than("scancode", "3.2.3"));
+    assert_eq!(
+        Some(false),
+        syn.tool_version_older_than("scancode", "3.2.2")
+    );
+    assert_eq!(
+        Some(false),
+        syn.tool_version_older_than("scancode", "3.0.0")
+    );
+    assert_eq!(None, syn.tool_version_older_than("fossology", "1.0.0"));
+    assert_eq!(
+        None,
+        syn.tool_version_older_than("scancode", "not-a-version")
+    );
+}
+
+#[test]
+fn definition_with_raw() {
+    let map: serde_json::Map<String, serde_json::Value> = serde_json::from_str(GET_DATA).unwrap();
+    let input = map["crate/cratesio/-/syn/1.0.14"].clone();
+
+    let with_raw: defs::DefinitionWithRaw = serde_json::from_value(input.clone()).unwrap();
+
+    assert_eq!(input, with_raw.raw);
+    assert_eq!("syn", with_raw.definition.coordinates.name);
+    assert_eq!(87, with_raw.definition.scores.effective);
+    assert!(with_raw.definition.is_harvested());
+}
+
+#[test]
+fn license_coverage() {
+    let facet = |files: u32, unknown: u32| -> defs::Facet {
+        serde_json::from_value(serde_json::json!({
+            "attribution": { "unknown": 0 },
+            "discovered": { "unknown": unknown, "expressions": [] },
+            "files": files,
+        }))
+        .unwrap()
+    };
+
+    assert_eq!(1.0, facet(10, 0).license_coverage());
+    assert_eq!(0.25, facet(8, 6).license_coverage());
+    assert_eq!(0.0, facet(8, 8).license_coverage());
+    assert_eq!(0.0, facet(0, 0).license_coverage());
+}
+
+#[test]
+fn meets_attribution() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(DEBIAN_DATA)
+        .unwrap();
+    let zlib = defs::GetResponse::try_from(resp)
+        .unwrap()
+        .definitions
+        .remove(0);
+
+    // 2 of the 4 files have attribution
+    assert_eq!(
+        0.5,
+        zlib.licensed
+            .as_ref()
+            .unwrap()
+            .facets
+            .core
+            .attribution_coverage()
+    );
+    assert!(zlib.meets_attribution(0.4));
+    assert!(zlib.meets_attribution(0.5));
+    assert!(!zlib.meets_attribution(0.6));
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    let tame_gcs = definitions
+        .iter()
+        .find(|def| def.coordinates.name == "tame-gcs")
+        .unwrap();
+
+    assert!(!tame_gcs.is_harvested());
+    assert!(!tame_gcs.meets_attribution(0.0));
+}
+
+#[test]
+fn coordinate_for_prefixed_version() {
+    let requested: Vec<cd::Coordinate> = ["crate/cratesio/-/syn/v1.0.14"]
+        .iter()
+        .map(|c| c.parse().unwrap())
+        .collect();
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+    let get = defs::GetResponse::try_from(resp).unwrap();
+
+    let syn = get
+        .definitions
+        .iter()
+        .find(|def| def.coordinates.name == "syn")
+        .unwrap();
+    assert_eq!(
+        Some(&requested[0]),
+        defs::GetResponse::coordinate_for(syn, &requested)
+    );
+    assert!(get.missing(&requested).is_empty());
+}
+
+#[test]
+fn coordinate_for() {
+    const SYN_SHA: &str = "855f331cf0e14916a1c3026786b59e6f6b6f2d6f";
+    const QUOTE_SHA: &str = "2f5e5e8f9d5e2e5d3c4b0f8c6e1d6a1f4d0b5c3a";
+
+    let original: Vec<cd::Coordinate> = [
+        format!("git/github/dtolnay/syn/{}", SYN_SHA),
+        format!("git/github/dtolnay/quote/{}/pr/12", QUOTE_SHA),
+    ]
+    .iter()
+    .map(|c| c.parse().unwrap())
+    .collect();
+
+    let body = serde_json::json!({
+        format!("git/github/dtolnay/syn/{}", SYN_SHA): {
+            "coordinates": { "type": "git", "provider": "github", "namespace": "dtolnay", "name": "syn", "revision": SYN_SHA },
+            "described": null,
+            "licensed": null,
+        },
+        format!("git/github/dtolnay/quote/{}/pr/12", QUOTE_SHA): {
+            "coordinates": { "type": "git", "provider": "github", "namespace": "dtolnay", "name": "quote", "revision": QUOTE_SHA },
+            "described": null,
+            "licensed": null,
+        },
+        "git/github/dtolnay/anyhow/0000000000000000000000000000000000000000": {
+            "coordinates": { "type": "git", "provider": "github", "namespace": "dtolnay", "name": "anyhow", "revision": "0000000000000000000000000000000000000000" },
+            "described": null,
+            "licensed": null,
+        },
+    });
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(serde_json::to_vec(&body).unwrap())
+        .unwrap();
+    let get = defs::GetResponse::try_from(resp).unwrap();
+
+    for def in &get.definitions {
+        let coord = defs::GetResponse::coordinate_for(def, &original);
+
+        match def.coordinates.name.as_str() {
+            "syn" => assert_eq!(Some(&original[0]), coord),
+            "quote" => {
+                let coord = coord.unwrap();
+                assert_eq!(Some("dtolnay"), coord.namespace.as_deref());
+                assert_eq!(Some(12), coord.curation_pr);
+            }
+            _ => assert!(coord.is_none()),
+        }
+    }
+}
+
+#[test]
+fn license_score_wrapped() {
+    let score = serde_json::json!({
+        "total": 75,
+        "declared": 30,
+        "discovered": 0,
+        "consistency": 15,
+        "spdx": 15,
+        "texts": 15,
+    });
+
+    let flat: defs::LicenseScore = serde_json::from_value(score.clone()).unwrap();
+    let wrapped: defs::LicenseScore =
+        serde_json::from_value(serde_json::json!({ "effective": score })).unwrap();
+
+    assert_eq!(
+        defs::LicenseScore {
+            total: 75,
+            declared: 30,
+            discovered: 0,
+            consistency: 15,
+            spdx: 15,
+            texts: 15,
+        },
+        flat
+    );
+    assert_eq!(flat, wrapped);
+}
+
+#[test]
+fn harvest_rate() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+
+    let mut get = defs::GetResponse::try_from(resp).unwrap();
+    // syn and tokio are harvested, tame-gcs is not
+    assert!((get.harvest_rate() - 2.0 / 3.0).abs() < f32::EPSILON);
+
+    get.definitions.retain(|def| !def.is_harvested());
+    assert_eq!(0.0, get.harvest_rate());
+
+    get.definitions.clear();
+    assert_eq!(0.0, get.harvest_rate());
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn to_csv() {
+    let mut data: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+    data["crate/cratesio/-/syn/1.0.14"]["licensed"]["declared"] = "MIT, Apache-2.0".into();
+
+    let definitions = [
+        defs::Definition::deserialize(&data["crate/cratesio/-/syn/1.0.14"]).unwrap(),
+        defs::Definition::deserialize(&data["crate/cratesio/-/tame-gcs/0.4.0"]).unwrap(),
+    ];
+
+    let mut csv = Vec::new();
+    defs::to_csv(&definitions, &mut csv).unwrap();
+
+    assert_eq!(
+        "coordinate,declared_license,effective_score,release_date,source_url\n\
+        crate/cratesio/-/syn/1.0.14,\"MIT, Apache-2.0\",87,2020-01-20,https://github.com/dtolnay/syn/tree/855f331cf0e14916a1c3026786b59e6f6b6f2d6f\n\
+        crate/cratesio/-/tame-gcs/0.4.0,,0,,\n",
+        String::from_utf8(csv).unwrap()
+    );
+}
+
+#[cfg(feature = "spdx-doc")]
+#[test]
+fn spdx_tag_value() {
+    let data: serde_json::Value = serde_json::from_str(GET_DATA).unwrap();
+
+    let syn = defs::Definition::deserialize(&data["crate/cratesio/-/syn/1.0.14"]).unwrap();
+    assert_eq!(
+        "PackageName: syn\n\
+        PackageVersion: 1.0.14\n\
+        PackageLicenseConcluded: Apache-2.0 AND MIT\n\
+        PackageDownloadLocation: https://crates.io/api/v1/crates/syn/1.0.14/download\n",
+        syn.to_spdx_tag_value()
+    );
+
+    let tame_gcs = defs::Definition::deserialize(&data["crate/cratesio/-/tame-gcs/0.4.0"]).unwrap();
+    assert_eq!(
+        "PackageName: tame-gcs\n\
+        PackageVersion: 0.4.0\n\
+        PackageLicenseConcluded: NOASSERTION\n\
+        PackageDownloadLocation: NOASSERTION\n",
+        tame_gcs.to_spdx_tag_value()
+    );
+}
+
+#[test]
+fn correlate() {
+    const SHA: &str = "855f331cf0e14916a1c3026786b59e6f6b6f2d6f";
+
+    let def = |shape: &str, provider: &str, ns: Option<&str>, name: &str, rev: &str| {
+        serde_json::json!({
+            "coordinates": { "type": shape, "provider": provider, "namespace": ns, "name": name, "revision": rev },
+            "described": null,
+            "licensed": null,
+        })
+    };
+
+    let body = serde_json::json!({
+        "crate/cratesio/-/syn/2.0.38": def("crate", "cratesio", None, "syn", "2.0.38"),
+        "crate/cratesio/-/syn/1.0.14": def("crate", "cratesio", None, "syn", "1.0.14"),
+        "nuget/nuget/-/newtonsoft.json/13.0.3": def("nuget", "nuget", None, "newtonsoft.json", "13.0.3"),
+        "pypi/pypi/-/zope-interface/5.5.2": def("pypi", "pypi", None, "zope-interface", "5.5.2"),
+        "git/github/dtolnay/syn/sha": def("git", "github", Some("dtolnay"), "syn", &SHA.to_uppercase()),
+        "maven/mavencentral/org.apache/Commons/1.0": def("maven", "mavencentral", Some("org.apache"), "Commons", "1.0"),
+    });
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(serde_json::to_vec(&body).unwrap())
+        .unwrap();
+    let get = defs::GetResponse::try_from(resp).unwrap();
+
+    let requested: Vec<cd::Coordinate> = [
+        "crate/cratesio/-/syn/1.0.14+build.1",
+        "crate/cratesio/-/syn/v2.0.38",
+        "crate/cratesio/-/syn/3.0.0",
+        "nuget/nuget/-/Newtonsoft.Json/13.0.3",
+        "pypi/pypi/-/Zope.Interface/5.5.2",
+        &format!("git/github/DTolnay/syn/{SHA}"),
+        // Maven is case-sensitive
+        "maven/mavencentral/org.apache/commons/1.0",
+        // Duplicates are paired with the same definition
+        "crate/cratesio/-/syn/1.0.14/pr/2",
+    ]
+    .iter()
+    .map(|c| c.parse().unwrap())
+    .collect();
+
+    let correlated = get.correlate(&requested);
+    assert_eq!(requested.len(), correlated.len());
+
+    let expected = [
+        Some("crate/cratesio/-/syn/1.0.14"),
+        Some("crate/cratesio/-/syn/2.0.38"),
+        None,
+        Some("nuget/nuget/-/newtonsoft.json/13.0.3"),
+        Some("pypi/pypi/-/zope-interface/5.5.2"),
+        Some("git/github/dtolnay/syn/855f331cf0e14916a1c3026786b59e6f6b6f2d6f"),
+        None,
+        Some("crate/cratesio/-/syn/1.0.14"),
+    ];
+
+    for ((coord, def), (req, expected)) in correlated.iter().zip(requested.iter().zip(expected)) {
+        assert_eq!(req, coord);
+        assert_eq!(
+            expected,
+            def.map(|def| def.coordinates.to_string()).as_deref(),
+            "{coord}"
+        );
+    }
+}
+
+#[test]
+fn deserialize_npm_scoped() {
+    let resp = http::Response::builder()
+        .status(200)
+        .body(NPM_DATA)
+        .unwrap();
+
+    let definitions = defs::GetResponse::try_from(resp).unwrap().definitions;
+    assert_eq!(1, definitions.len());
+
+    let coords = &definitions[0].coordinates;
+    assert_eq!(cd::Shape::Npm, coords.shape);
+    assert_eq!(cd::Provider::Npmjs, coords.provider);
+    assert_eq!(Some("@types"), coords.namespace.as_deref());
+    assert_eq!("node", coords.name);
+    assert_eq!(
+        Some("https://npmjs.com/package/@types/node/v/18.11.9"),
+        coords.url.as_deref()
+    );
+    assert_eq!("npm/npmjs/@types/node/18.11.9", coords.to_string());
+
+    // Coordinates without a namespace or url still deserialize
+    let resp = http::Response::builder()
+        .status(200)
+        .body(GET_DATA)
+        .unwrap();
+    let get = defs::GetResponse::try_from(resp).unwrap();
+    let syn = get
+        .definitions
+        .iter()
+        .find(|def| def.coordinates.name == "syn")
+        .unwrap();
+    assert_eq!(None, syn.coordinates.namespace);
+    assert_eq!(None, syn.coordinates.url);
+    assert_eq!("crate/cratesio/-/syn/1.0.14", syn.coordinates.to_string());
+}
+
+#[test]
+fn def_coords_to_coordinate() {
+    for data in [
+        GET_DATA,
+        MAVEN_DATA,
+        MAVEN_GOOGLE_DATA,
+        RUBYGEMS_DATA,
+        DEBIAN_DATA,
+        DEBSRC_DATA,
+        NPM_DATA,
+    ] {
+        // The keys of the response are the requested coordinates
+        let mut requested: Vec<cd::Coordinate> =
+            serde_json::from_str::<std::collections::BTreeMap<String, serde_json::Value>>(data)
+                .unwrap()
+                .keys()
+                .map(|key| key.parse().unwrap())
+                .collect();
+        requested.sort();
+
+        let resp = http::Response::builder().status(200).body(data).unwrap();
+        let get = defs::GetResponse::try_from(resp).unwrap();
+
+        for def in &get.definitions {
+            let coord = def.coordinates.to_coordinate();
+            assert!(requested.contains(&coord), "{coord}");
+            assert_eq!(def.coordinates.to_string(), coord.to_string());
+            assert!(coord.matches(&def.coordinates));
+        }
+
+        let mut converted: Vec<cd::Coordinate> = get
+            .definitions
+            .into_iter()
+            .map(|def| def.coordinates.into())
+            .collect();
+        converted.sort();
+        assert_eq!(requested, converted);
+    }
+
+    // Commit shas are parsed for git coordinates
+    const SHA: &str = "855f331cf0e14916a1c3026786b59e6f6b6f2d6f";
+    let def_coords: defs::DefCoords = serde_json::from_value(serde_json::json!({
+        "type": "git", "provider": "github", "namespace": "dtolnay", "name": "syn", "revision": SHA,
+    }))
+    .unwrap();
+    let coord = cd::Coordinate::from(def_coords);
+    assert!(coord.version.as_sha().is_some());
+    assert_eq!(None, coord.curation_pr);
+    assert_eq!(
+        format!("git/github/dtolnay/syn/{SHA}")
+            .parse::<cd::Coordinate>()
+            .unwrap(),
+        coord
+    );
+}
+
+#[test]
+fn partial_meta() {
+    let body = serde_json::json!({
+        "crate/cratesio/-/syn/1.0.14": {
+            "coordinates": { "type": "crate", "provider": "cratesio", "name": "syn", "revision": "1.0.14" },
+            "described": null,
+            "licensed": null,
+            "_meta": { "schemaVersion": "1.6.1" },
+        },
+        "crate/cratesio/-/quote/1.0.33": {
+            "coordinates": { "type": "crate", "provider": "cratesio", "name": "quote", "revision": "1.0.33" },
+            "described": null,
+            "licensed": null,
+            "_meta": { "schemaVersion": "1.6.1", "updated": "2023-10-28T01:02:03.456Z" },
+        },
+    });
+
+    let resp = http::Response::builder()
+        .status(200)
+        .body(serde_json::to_vec(&body).unwrap())
+        .unwrap();
+
+    // A malformed `_meta` doesn't fail the rest of the response
+    let get = defs::GetResponse::try_from(resp).unwrap();
+    assert_eq!(2, get.definitions.len());
+
+    for def in &get.definitions {
+        match def.coordinates.name.as_str() {
+            "syn" => assert!(def.meta.is_none()),
+            "quote" => assert_eq!(
+                "2023-10-28T01:02:03.456Z",
+                def.meta.as_ref().unwrap().updated
+            ),
+            other => panic!("unexpected definition {other}"),
+        }
+    }
+}
+
+#[test]
+fn def_coords_display() {
+    const SHA: &str = "855f331cf0e14916a1c3026786b59e6f6b6f2d6f";
+
+    for (coords, expected) in [
+        (
+            serde_json::json!({ "type": "go", "provider": "golang", "namespace": "github.com/stretchr", "name": "testify", "revision": "v1.8.0" }),
+            "go/golang/github.com%2fstretchr/testify/v1.8.0",
+        ),
+        (
+            serde_json::json!({ "type": "go", "provider": "golang", "namespace": "github.com/BurntSushi", "name": "toml", "revision": "v1.3.2" }),
+            "go/golang/github.com%2f!burnt!sushi/toml/v1.3.2",
+        ),
+        (
+            serde_json::json!({ "type": "git", "provider": "gitlab", "namespace": "group/subgroup", "name": "fork", "revision": SHA }),
+            "git/gitlab/group%2fsubgroup/fork/855f331cf0e14916a1c3026786b59e6f6b6f2d6f",
+        ),
+    ] {
+        let coords: defs::DefCoords = serde_json::from_value(coords).unwrap();
+
+        // The display is the same canonical form as the coordinate
+        assert_eq!(expected, coords.to_string());
+        assert_eq!(coords.to_coordinate().to_string(), coords.to_string());
+        assert_eq!(
+            coords.to_coordinate(),
+            coords.to_string().parse::<cd::Coordinate>().unwrap()
+        );
+    }
+}
+
+#[test]
+fn def_coords_decoded() {
+    // The API may return the namespace and name either encoded or decoded,
+    // but they are always decoded once deserialized
+    for namespace in ["github.com%2fstretchr", "github.com/stretchr"] {
+        let coords: defs::DefCoords = serde_json::from_value(serde_json::json!({
+            "type": "go", "provider": "golang", "namespace": namespace, "name": "testify", "revision": "v1.8.0",
+        }))
+        .unwrap();
+
+        assert_eq!(Some("github.com/stretchr"), coords.namespace.as_deref());
+        assert_eq!(
+            "go/golang/github.com%2fstretchr/testify/v1.8.0",
+            coords.to_string()
+        );
+
+        let coord = cd::Coordinate::from(coords);
+        assert_eq!(Some("github.com/stretchr"), coord.namespace.as_deref());
+        assert_eq!(
+            coord,
+            "go/golang/github.com%2fstretchr/testify/v1.8.0"
+                .parse::<cd::Coordinate>()
+                .unwrap()
+        );
+    }
+
+    let coords: defs::DefCoords = serde_json::from_value(serde_json::json!({
+        "type": "go", "provider": "golang", "namespace": "github.com%2f!burnt!sushi", "name": "toml", "revision": "v1.3.2",
+    }))
+    .unwrap();
+    assert_eq!(Some("github.com/BurntSushi"), coords.namespace.as_deref());
+    assert_eq!(
+        "go/golang/github.com%2f!burnt!sushi/toml/v1.3.2",
+        coords.to_string()
+    );
+
+    // Segments that aren't validly encoded are kept as is
+    let coords: defs::DefCoords = serde_json::from_value(serde_json::json!({
+        "type": "crate", "provider": "cratesio", "name": "odd%ff", "revision": "1.0.0",
+    }))
+    .unwrap();
+    assert_eq!("odd%ff", coords.name);
+}
@@ -0,0 +1,23 @@
+use cd::error::is_retryable_status;
+use http::StatusCode;
+
+#[test]
+fn retryable_statuses() {
+    for code in [429, 500, 502, 503, 504] {
+        let status = StatusCode::from_u16(code).unwrap();
+        assert!(is_retryable_status(status), "{code} should be retryable");
+        assert!(cd::Error::from(status).is_retryable());
+    }
+
+    for code in [200, 206, 400, 401, 403, 404, 422, 501] {
+        let status = StatusCode::from_u16(code).unwrap();
+        assert!(
+            !is_retryable_status(status),
+            "{code} should not be retryable"
+        );
+        assert!(!cd::Error::from(status).is_retryable());
+    }
+
+    let json_err = serde_json::from_str::<u32>("nope").unwrap_err();
+    assert!(!cd::Error::from(json_err).is_retryable());
+}